@@ -2,7 +2,11 @@
 
 const INPUT: &'static str = include_str!("../input");
 
-pub fn solve() -> () {}
+pub fn solve() -> () {
+    solve_input(INPUT)
+}
+
+pub fn solve_input(input: &str) -> () {}
 
 fn parse(input: &str) -> () {}
 
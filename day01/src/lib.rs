@@ -3,7 +3,7 @@
 const TARGET_YEAR: usize = 2020;
 const INPUT: &'static str = include_str!("../input");
 
-trait Parser {
+pub trait Parser {
     fn parse(input: &str) -> Vec<usize>;
 }
 
@@ -31,24 +31,31 @@ impl Parser for SortedParser {
 
 // ============================================================================================== //
 
-trait SearchAlg {
+pub trait SearchAlg {
+    /// Parser producing values in the order this algorithm expects
     type Parser: Parser;
 
-    fn search(&self, expenses: &[usize], sum: usize) -> Option<usize>;
+    /// Search [values] for two values, at different positions, that sum to [sum]
+    fn find_pair(&self, values: &[usize], sum: usize) -> Option<(usize, usize)>;
+
+    /// Search [expenses] for two values that sum to [sum]
+    /// returns the product of the values
+    fn search(&self, expenses: &[usize], sum: usize) -> Option<usize> {
+        self.find_pair(expenses, sum).map(|(a, b)| a * b)
+    }
 }
 
-struct BruteForce;
+pub struct BruteForce;
 
 impl SearchAlg for BruteForce {
     type Parser = UnsortedParser;
 
-    /// Search [expenses] for two values that sum to [sum] using a brute force algorithm
-    /// returns the product of the values
-    fn search(&self, expenses: &[usize], sum: usize) -> Option<usize> {
-        for i in 0..(expenses.len() - 1) {
-            for j in (i + 1)..expenses.len() {
-                if expenses[i] + expenses[j] == sum {
-                    return Some(expenses[i] * expenses[j]);
+    /// Search [values] for two values that sum to [sum] using a brute force algorithm
+    fn find_pair(&self, values: &[usize], sum: usize) -> Option<(usize, usize)> {
+        for i in 0..values.len().saturating_sub(1) {
+            for j in (i + 1)..values.len() {
+                if values[i] + values[j] == sum {
+                    return Some((values[i], values[j]));
                 }
             }
         }
@@ -57,21 +64,24 @@ impl SearchAlg for BruteForce {
     }
 }
 
-struct TwoPointer;
+pub struct TwoPointer;
 
 impl SearchAlg for TwoPointer {
     type Parser = SortedParser;
 
-    /// Search _sorted_ [expenses] for two values that sum to [sum] using a two pointer algorithm
-    /// returns the product of the values
-    fn search(&self, expenses: &[usize], sum: usize) -> Option<usize> {
+    /// Search _sorted_ [values] for two values that sum to [sum] using a two pointer algorithm
+    fn find_pair(&self, values: &[usize], sum: usize) -> Option<(usize, usize)> {
+        if values.is_empty() {
+            return None;
+        }
+
         let mut l = 0;
-        let mut r = expenses.len() - 1;
+        let mut r = values.len() - 1;
 
         while l < r {
-            if expenses[l] + expenses[r] == sum {
-                return Some(expenses[l] * expenses[r]);
-            } else if expenses[l] + expenses[r] < sum {
+            if values[l] + values[r] == sum {
+                return Some((values[l], values[r]));
+            } else if values[l] + values[r] < sum {
                 l += 1;
             } else {
                 r -= 1;
@@ -121,8 +131,12 @@ fn parse_and_solve_both_parts<P: Parser>(
     )
 }
 
+pub fn solve_input(input: &str) -> (usize, usize) {
+    parse_and_solve_both_parts(input, &TwoPointer)
+}
+
 pub fn solve() -> (usize, usize) {
-    parse_and_solve_both_parts(&INPUT, &TwoPointer)
+    solve_input(INPUT)
 }
 
 // ============================================================================================== //
@@ -38,7 +38,11 @@ impl <'a> PasswordDbEntry<'a> {
 }
 
 pub fn solve() -> (usize, usize) {
-    INPUT.lines()
+    solve_input(INPUT)
+}
+
+pub fn solve_input(input: &str) -> (usize, usize) {
+    input.lines()
         .filter_map(PasswordDbEntry::parse)
         .fold((0, 0), |(p1, p2), entry|
             (p1 + entry.is_part_1_valid() as usize, p2 + entry.is_part_2_valid() as usize)
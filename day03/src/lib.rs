@@ -5,25 +5,27 @@ use crate::forest::Forest;
 const INPUT: &'static str = include_str!("../input");
 
 mod forest {
+    use grid::Grid;
+
     const TREE: u8 = '#' as u8;
 
-    pub struct Forest<'a>(Vec<&'a[u8]>);
+    pub struct Forest(Grid);
 
-    impl <'a> Forest<'a> {
+    impl Forest {
         pub fn parse(input: &str) -> Forest {
-            Forest(input.lines().map(str::as_bytes).collect())
+            Forest(Grid::parse(input).expect("failed to parse forest"))
         }
 
         pub fn width(&self) -> usize {
-            self.0[0].len()
+            self.0.width()
         }
 
         pub fn height(&self) -> usize {
-            self.0.len()
+            self.0.height()
         }
 
         pub fn has_tree(&self, x: usize, y: usize) -> bool {
-            self.0[y][x] == TREE
+            self.0.get(x, y) == TREE
         }
     }
 }
@@ -32,7 +34,7 @@ trait TreeCounter {
     fn count_trees(&self, right: usize, down: usize) -> usize;
 }
 
-impl <'a> TreeCounter for Forest<'a> {
+impl TreeCounter for Forest {
     fn count_trees(&self, right: usize, down: usize) -> usize {
         let width = self.width();
         let height = self.height();
@@ -64,7 +66,11 @@ fn solve_part_2(forest: &Forest) -> usize {
 }
 
 pub fn solve() -> (usize, usize) {
-    let forest = &Forest::parse(INPUT);
+    solve_input(INPUT)
+}
+
+pub fn solve_input(input: &str) -> (usize, usize) {
+    let forest = &Forest::parse(input);
     (solve_part_1(forest), solve_part_2(forest))
 }
 
@@ -0,0 +1,261 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::parser::parse_well_formed;
+use crate::{Record, Schema};
+
+/// File formats a batch of passports can be converted between
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// The puzzle's blank line separated `key:value` records
+    Records,
+    /// A header row of keys, then one row per passport. Missing fields are empty cells, fields
+    /// that are present but empty are written as `""`
+    Csv,
+    /// One JSON object of string values per line
+    JsonLines,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConvertError {
+    /// 1-based line of the input that couldn't be parsed
+    Syntax { line: usize, message: String },
+    /// The key or value can't be represented in the records format
+    InvalidField { line: usize, key: String, value: String },
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvertError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+            ConvertError::InvalidField { line, key, value } => {
+                write!(f, "line {}: field {:?} with value {:?} can't be written as key:value", line, key, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {}
+
+/// Converts passports between [Format]s.
+///
+/// Fields are written in the order of [schema], followed by any other keys in alphabetical order.
+/// Duplicate keys are collapsed to their last value. Passports without any fields are dropped, as
+/// the records format has no way to write them apart from the blank lines around them.
+#[derive(Debug, Clone, Copy)]
+pub struct Converter<'s> {
+    pub schema: &'s Schema,
+    /// Drop every passport that doesn't pass [Schema::validate]
+    pub only_valid: bool,
+}
+
+impl<'s> Converter<'s> {
+    pub fn new(schema: &'s Schema) -> Converter<'s> {
+        Converter { schema, only_valid: false }
+    }
+
+    pub fn only_valid(self, only_valid: bool) -> Converter<'s> {
+        Converter { only_valid, ..self }
+    }
+
+    pub fn convert(&self, input: &str, from: Format, to: Format) -> Result<String, ConvertError> {
+        let records = match from {
+            Format::Records => input.to_owned(),
+            Format::Csv => csv_to_records(input)?,
+            Format::JsonLines => json_lines_to_records(input)?,
+        };
+        let records = parse_well_formed(&records).map_err(|(position, token)| ConvertError::Syntax {
+            line: position.line,
+            message: format!("malformed token {:?}, expected key:value", token),
+        })?;
+        Ok(self.export(&records, to))
+    }
+
+    pub fn export(&self, records: &[Record], format: Format) -> String {
+        let records = records.iter()
+            .filter(|record| !record.fields().is_empty())
+            .filter(|record| !self.only_valid || self.schema.validate(record))
+            .collect::<Vec<_>>();
+        let keys = self.keys(&records);
+
+        let mut out = String::new();
+        match format {
+            Format::Records => {
+                let records = records.iter()
+                    .map(|record| keys.iter()
+                        .filter_map(|key| record.get(key).map(|value| format!("{}:{}", key, value)))
+                        .collect::<Vec<_>>()
+                        .join(" "))
+                    .collect::<Vec<_>>();
+                out.push_str(&join_records(&records));
+            }
+            Format::Csv => {
+                out.push_str(&keys.iter().map(|key| csv_cell(Some(key))).collect::<Vec<_>>().join(","));
+                out.push('\n');
+                for record in records {
+                    out.push_str(&keys.iter().map(|key| csv_cell(record.get(key))).collect::<Vec<_>>().join(","));
+                    out.push('\n');
+                }
+            }
+            Format::JsonLines => {
+                for record in records {
+                    let fields = keys.iter()
+                        .filter_map(|key| record.get(key).map(|value| {
+                            format!("{}:{}", Value::from(key.as_str()), Value::from(value))
+                        }))
+                        .collect::<Vec<_>>();
+                    out.push('{');
+                    out.push_str(&fields.join(","));
+                    out.push_str("}\n");
+                }
+            }
+        }
+        out
+    }
+
+    /// Normalized field order, schema keys first then every other key seen in [records]
+    fn keys(&self, records: &[&Record]) -> Vec<String> {
+        let known = self.schema.fields.iter().map(|spec| spec.key.clone()).collect::<Vec<_>>();
+        let extra = records.iter()
+            .flat_map(|record| record.fields().iter().map(|field| field.key))
+            .filter(|key| !known.iter().any(|known| known == key))
+            .map(str::to_owned)
+            .collect::<BTreeSet<_>>();
+
+        known.into_iter().chain(extra).collect()
+    }
+}
+
+fn csv_cell(value: Option<&str>) -> String {
+    match value {
+        None => String::new(),
+        Some(value) if value.is_empty() || value.contains(&[',', '"'][..]) => {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        }
+        Some(value) => value.to_owned(),
+    }
+}
+
+/// Splits a single CSV row, `None` for empty unquoted cells
+fn csv_row(line: &str, line_number: usize) -> Result<Vec<Option<String>>, ConvertError> {
+    let syntax = |message: String| ConvertError::Syntax { line: line_number, message };
+    let mut cells = vec![];
+    let mut chars = line.chars().peekable();
+
+    'cells: loop {
+        let mut cell = String::new();
+
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        cell.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => cell.push(c),
+                    None => return Err(syntax("unterminated quoted cell".to_owned())),
+                }
+            }
+            cells.push(Some(cell));
+
+            match chars.next() {
+                Some(',') => continue 'cells,
+                None => return Ok(cells),
+                Some(c) => return Err(syntax(format!("expected ',' after quoted cell, found {:?}", c))),
+            }
+        }
+
+        loop {
+            match chars.next() {
+                Some(',') => {
+                    cells.push(Some(cell).filter(|cell| !cell.is_empty()));
+                    continue 'cells;
+                }
+                Some(c) => cell.push(c),
+                None => {
+                    cells.push(Some(cell).filter(|cell| !cell.is_empty()));
+                    return Ok(cells);
+                }
+            }
+        }
+    }
+}
+
+fn csv_to_records(input: &str) -> Result<String, ConvertError> {
+    let mut lines = input.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    let header = match lines.next() {
+        Some((i, line)) => csv_row(line, i + 1)?,
+        None => return Ok(String::new()),
+    };
+    let keys = header.into_iter()
+        .map(Option::unwrap_or_default)
+        .collect::<Vec<_>>();
+
+    let mut records = vec![];
+    for (i, line) in lines {
+        let cells = csv_row(line, i + 1)?;
+        if cells.len() != keys.len() {
+            return Err(ConvertError::Syntax {
+                line: i + 1,
+                message: format!("expected {} cells, found {}", keys.len(), cells.len()),
+            });
+        }
+
+        let fields = keys.iter().zip(cells)
+            .filter_map(|(key, value)| value.map(|value| (key.as_str(), value)));
+        records.push(record_line(fields, i + 1)?);
+    }
+
+    Ok(join_records(&records))
+}
+
+fn json_lines_to_records(input: &str) -> Result<String, ConvertError> {
+    let mut records = vec![];
+
+    for (i, line) in input.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let syntax = |message: String| ConvertError::Syntax { line: i + 1, message };
+
+        let object = serde_json::from_str::<Map<String, Value>>(line)
+            .map_err(|e| syntax(e.to_string()))?;
+        let fields = object.iter()
+            .map(|(key, value)| match value {
+                Value::String(value) => Ok((key.as_str(), value.clone())),
+                _ => Err(syntax(format!("value of {:?} must be a string", key))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        records.push(record_line(fields.into_iter(), i + 1)?);
+    }
+
+    Ok(join_records(&records))
+}
+
+/// Separates [records] by blank lines, dropping empty ones
+fn join_records(records: &[String]) -> String {
+    let records = records.iter().filter(|record| !record.is_empty()).map(String::as_str).collect::<Vec<_>>();
+    if records.is_empty() {
+        String::new()
+    } else {
+        records.join("\n\n") + "\n"
+    }
+}
+
+/// Formats a single record as `key:value` pairs, rejecting anything [parse] would split differently
+fn record_line<'a>(fields: impl Iterator<Item=(&'a str, String)>, line: usize) -> Result<String, ConvertError> {
+    fields
+        .map(|(key, value)| {
+            let invalid = key.is_empty() ||
+                key.contains(|c: char| c == ':' || c.is_whitespace()) ||
+                value.contains(char::is_whitespace);
+            if invalid {
+                Err(ConvertError::InvalidField { line, key: key.to_owned(), value })
+            } else {
+                Ok(format!("{}:{}", key, value))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|fields| fields.join(" "))
+}
@@ -1,93 +1,62 @@
 #![cfg_attr(feature = "unstable", feature(test))]
 
+use std::io::{self, BufRead};
+
+use records::Records;
+
+use crate::parser::parse;
+
+pub use crate::convert::{ConvertError, Converter, Format};
+pub use crate::parser::{parse_with, Diagnostic, Field, Issue, Parsed, Position, Record, Severity, Strictness};
+pub use crate::passport::{EyeColor, HairColor, Height, ParseFieldError, Passport, PassportError};
+pub use crate::report::{RecordReport, Report};
+pub use crate::schema::{FieldSpec, Pattern, Reason, Rule, Schema, SchemaError, Unit, Violation};
+
+mod convert;
+mod parser;
+mod passport;
+mod report;
+mod schema;
+
 const INPUT: &'static str = include_str!("../input");
 
 // =====
 
-fn parse(input: &str) -> Vec<[Option<&str>; 7]> {
-    input.split("\n\n")
-        .map(|line| {
-            line.split(&[' ', '\n'][..])
-                .filter_map(|entry| {
-                    let mut split = entry.split(':');
-                    split.next().and_then(|key| {
-                        split.next().map(|value| (key, value))
-                    })
-                })
-                .fold(Default::default(), |mut acc: [Option<&str>; 7], (key, value)| {
-                    if let Ok(i) = ["byr", "ecl", "eyr", "hcl", "hgt", "iyr", "pid"]
-                        .binary_search(&key) {
-                        acc[i] = Some(value)
-                    }
-                    acc
-                })
-        }).collect()
+/// Validates every passport in [input] against [schema], listing each violation
+pub fn report(input: &str, schema: &Schema) -> Report {
+    Report::new(&parse(input), schema)
 }
 
-fn validate_part_1(passport: &[Option<&str>; 7]) -> bool {
-    passport.iter().all(Option::is_some)
+fn validate_part_1(schema: &Schema, passport: &Record) -> bool {
+    schema.has_required_fields(passport)
 }
 
-fn validate_part_2(passport: &[Option<&str>; 7]) -> bool {
-    passport[0].and_then(|value| value.parse::<usize>().ok())
-        .filter(|x| (1920..=2002).contains(x))
-        .is_some() &&
-
-        // ecl
-        passport[1].and_then(|value| {
-            ["amb", "blu", "brn", "grn", "gry", "hzl", "oth"]
-                .binary_search(&value)
-                .ok()
-        }).is_some() &&
-
-        // eyr
-        passport[2].and_then(|value| value.parse::<usize>().ok())
-            .filter(|x| (2010..=2030).contains(x))
-            .is_some() &&
-
-        // "hcl"
-        passport[3].filter(|value| {
-            let bytes = value.as_bytes();
-            bytes.len() == 7 &&
-                bytes[0] == '#' as u8 &&
-                bytes[1..].iter().all(|&b| b.is_ascii_hexdigit())
-        }).is_some() &&
-
-        // hgt
-        passport[4].and_then(|value| {
-            let (height, unit) = value.split_at(value.len() - 2);
-            height.parse::<usize>().ok()
-                .filter(|h| {
-                    match unit {
-                        "cm" => (150..=193).contains(h),
-                        "in" => (59..=76).contains(h),
-                        _ => false
-                    }
-                })
-        }).is_some() &&
-
-        // iyr
-        passport[5].and_then(|value| value.parse::<usize>().ok())
-            .filter(|x| (2010..=2020).contains(x))
-            .is_some() &&
-
-        // pid
-        passport[6].filter(|value| {
-            value.as_bytes().len() == 9 && value.chars().all(|c| c.is_ascii_digit())
-        }).is_some()
+fn validate_part_2(schema: &Schema, passport: &Record) -> bool {
+    schema.validate(passport)
 }
 
-fn count_valid(passports: &[[Option<&str>; 7]], validator: impl Fn(&[Option<&str>; 7]) -> bool) -> usize {
-    passports.iter()
-        .filter(|&passport| {
-            validator(passport)
-        })
-        .count()
+/// Counts the passports of [reader] valid for part 1 and part 2 of [schema], reading one record at
+/// a time so the whole input is never held in memory
+pub fn solve_reader<R: BufRead>(reader: R, schema: &Schema) -> io::Result<(usize, usize)> {
+    let mut counts = (0, 0);
+
+    for chunk in Records::new(reader) {
+        let chunk = chunk?;
+        for passport in &parse(chunk.text()) {
+            counts.0 += validate_part_1(schema, passport) as usize;
+            counts.1 += validate_part_2(schema, passport) as usize;
+        }
+    }
+
+    Ok(counts)
 }
 
 pub fn solve() -> (usize, usize) {
-    let passports = &parse(INPUT);
-    (count_valid(passports, crate::validate_part_1), count_valid(passports, crate::validate_part_2))
+    solve_input(INPUT)
+}
+
+pub fn solve_input(input: &str) -> (usize, usize) {
+    solve_reader(input.as_bytes(), &Schema::puzzle()).expect("reading from memory can't fail")
 }
 
 // ============================================================================================== //
@@ -95,19 +64,26 @@ pub fn solve() -> (usize, usize) {
 #[cfg(test)]
 mod tests {
     use crate::*;
+    use std::convert::TryFrom;
 
     const SAMPLE_01: &'static str = include_str!("../sample01");
     const INVALID: &'static str = include_str!("../invalid");
     const VALID: &'static str = include_str!("../valid");
 
+    fn count_valid(passports: &[Record], validator: impl Fn(&Record) -> bool) -> usize {
+        passports.iter()
+            .filter(|&passport| {
+                validator(passport)
+            })
+            .count()
+    }
+
     fn solve_part_1(input: &str) -> usize {
-        let passports = &parse(input);
-        count_valid(passports, crate::validate_part_1)
+        solve_input(input).0
     }
 
     fn solve_part_2(input: &str) -> usize {
-        let passports = &parse(input);
-        count_valid(passports, crate::validate_part_2)
+        solve_input(input).1
     }
 
     #[test]
@@ -146,6 +122,340 @@ mod tests {
         assert_eq!(actual, 172);
     }
 
+    #[test]
+    fn typed_passport_from_record() {
+        let passports = parse(VALID);
+        let passport = Passport::try_from(&passports[0]).unwrap();
+
+        assert_eq!(passport, Passport {
+            byr: 1980,
+            iyr: 2012,
+            eyr: 2030,
+            hgt: Height::In(74),
+            hcl: HairColor { r: 0x62, g: 0x3a, b: 0x2f },
+            ecl: EyeColor::Grn,
+            pid: "087499704",
+            cid: None,
+        });
+        assert_eq!(Passport::try_from(&passports[1]).unwrap().cid, Some("129"));
+    }
+
+    #[test]
+    fn typed_passport_errors() {
+        let passports = parse(INVALID);
+
+        assert_eq!(Passport::try_from(&passports[0]), Err(PassportError::Invalid("hgt")));
+        assert_eq!(Passport::try_from(&passports[2]), Err(PassportError::Invalid("hcl")));
+        assert_eq!(Passport::try_from(&parse("byr:1920")[0]), Err(PassportError::Missing("iyr")));
+        assert_eq!("190".parse::<Height>(), Err(ParseFieldError));
+    }
+
+    #[test]
+    fn schema_rules() {
+        let schema = Schema::puzzle();
+        let rule = |key: &str| &schema.fields.iter().find(|spec| spec.key == key).unwrap().rule;
+
+        assert!(rule("byr").matches("2002"));
+        assert!(!rule("byr").matches("2003"));
+        assert!(rule("hgt").matches("60in"));
+        assert!(rule("hgt").matches("190cm"));
+        assert!(!rule("hgt").matches("190in"));
+        assert!(!rule("hgt").matches("190"));
+
+        let violation = |value: &str| Violation {
+            key: "hgt".to_owned(),
+            value: Some(value.to_owned()),
+            reason: rule("hgt").check(value).unwrap_err(),
+        };
+        assert_eq!(violation("190").to_string(), "hgt: 190 has no unit, expected one of cm, in");
+        assert_eq!(violation("190ft").to_string(), "hgt: 190ft unit not accepted, expected one of cm, in");
+        assert!(rule("hcl").matches("#123abc"));
+        assert!(!rule("hcl").matches("#123abz"));
+        assert!(!rule("hcl").matches("123abc"));
+        assert!(rule("ecl").matches("brn"));
+        assert!(!rule("ecl").matches("wat"));
+        assert!(rule("pid").matches("000000001"));
+        assert!(!rule("pid").matches("0123456789"));
+    }
+
+    #[test]
+    fn expiration_year_follows_puzzle() {
+        // the original solver accepted eyr in 2010..=2030, the puzzle text says 2020..=2030
+        let schema = Schema::puzzle();
+        let rule = &schema.fields.iter().find(|spec| spec.key == "eyr").unwrap().rule;
+
+        assert_eq!(rule.check("2015"), Err(Reason::OutOfRange { min: 2020, max: 2030 }));
+        assert_eq!(rule.check("2019"), Err(Reason::OutOfRange { min: 2020, max: 2030 }));
+        assert_eq!(rule.check("2020"), Ok(()));
+        assert_eq!(rule.check("2030"), Ok(()));
+
+        let passport = VALID.split("\n\n").next().unwrap().replace("eyr:2030", "eyr:2015");
+        assert_eq!(solve_part_2(&passport), 0);
+        assert_eq!(solve_part_2(&passport.replace("eyr:2015", "eyr:2025")), 1);
+    }
+
+    #[test]
+    fn report_valid_passports() {
+        let report = report(VALID, &Schema::puzzle());
+
+        assert_eq!(report.valid_count(), 4);
+        assert_eq!(report.0.iter().map(|record| record.lines.clone()).collect::<Vec<_>>(),
+                   vec![1..=2, 4..=5, 7..=10, 12..=12]);
+    }
+
+    #[test]
+    fn report_invalid_passports() {
+        let report = report(INVALID, &Schema::puzzle());
+        let messages = |i: usize| report.0[i].violations.iter()
+            .map(Violation::to_string)
+            .collect::<Vec<_>>();
+
+        assert_eq!(report.valid_count(), 0);
+        assert_eq!(messages(0), vec![
+            "eyr: 1972 out of range 2020..=2030",
+            "hgt: 170 has no unit, expected one of cm, in",
+            "pid: 186cm contains non-digits",
+        ]);
+        assert_eq!(messages(2), vec!["hcl: missing '#'"]);
+        assert_eq!(messages(3), vec![
+            "byr: 2007 out of range 1920..=2002",
+            "iyr: 2023 out of range 2010..=2020",
+            "eyr: 2038 out of range 2020..=2030",
+            "hgt: 59cm out of range 150..=193",
+            "hcl: missing '#'",
+            "ecl: zzz is not one of amb, blu, brn, gry, grn, hzl, oth",
+            "pid: expected 9 digits, found 10",
+        ]);
+        assert_eq!(report.0[3].lines, 11..=13);
+    }
+
+    #[test]
+    fn report_text_and_json() {
+        let report = report(INVALID, &Schema::puzzle());
+
+        let text = report.to_string();
+        assert!(text.starts_with("record 0 (lines 1-2): invalid\n    eyr: 1972 out of range 2020..=2030\n"));
+        assert!(text.ends_with("0 of 4 valid"));
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["total"], 4);
+        assert_eq!(json["records"][2]["first_line"], 8);
+        assert_eq!(json["records"][2]["violations"][0]["field"], "hcl");
+        assert_eq!(json["records"][2]["violations"][0]["value"], "dab227");
+        assert_eq!(json["records"][2]["violations"][0]["message"], "hcl: missing '#'");
+    }
+
+    fn rules_path(name: &str) -> String {
+        format!("{}/rules/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    #[test]
+    fn load_puzzle_rules() {
+        let schema = Schema::load(rules_path("puzzle.toml")).unwrap();
+        assert_eq!(schema, Schema::puzzle());
+        assert_eq!(schema, Schema::default());
+    }
+
+    #[test]
+    fn load_alternate_rules() {
+        let schema = Schema::load(rules_path("north_pole.json")).unwrap();
+        let report = report(VALID, &schema);
+
+        let messages = |i: usize| report.0[i].violations.iter()
+            .map(Violation::to_string)
+            .collect::<Vec<_>>();
+
+        assert_eq!(messages(0), vec!["hgt: 74in unit not accepted, expected one of cm", "cid: missing"]);
+        assert_eq!(messages(2), vec!["cid: expected 3 digits, found 2"]);
+        assert_eq!(report.valid_count(), 1);
+    }
+
+    #[test]
+    fn pattern_rule() {
+        let schema = Schema::from_toml(r##"
+            [[fields]]
+            key = "hcl"
+            type = "pattern"
+            pattern = "#[0-9a-f]{6}|[a-z]+"
+        "##).unwrap();
+
+        assert_eq!(schema.violations(&parse("hcl:#123abc")[0]), vec![]);
+        assert_eq!(schema.violations(&parse("hcl:brown")[0]), vec![]);
+        assert_eq!(schema.violations(&parse("hcl:#123abcd")[0])[0].to_string(),
+                   "hcl: #123abcd does not match #[0-9a-f]{6}|[a-z]+");
+    }
+
+    #[test]
+    fn invalid_rules() {
+        assert!(matches!(Schema::from_json(r#"{"fields": [{"key": "x", "type": "colour"}]}"#),
+                         Err(SchemaError::Json(_))));
+        assert!(matches!(Schema::from_toml("[[fields]]\nkey = \"x\"\ntype = \"pattern\"\npattern = \"(\""),
+                         Err(SchemaError::Toml(_))));
+        assert!(matches!(Schema::load(rules_path("missing.toml")), Err(SchemaError::Io(_))));
+        assert!(matches!(Schema::load(rules_path("puzzle.yaml")), Err(SchemaError::UnknownFormat(_))));
+    }
+
+    #[test]
+    fn unknown_keys_and_empty_ranges() {
+        let error = |rules: &str| Schema::from_toml(rules).unwrap_err().to_string();
+
+        // typos are rejected whether the key belongs to the field or to its rule
+        assert!(error("[[fields]]\nkey = \"x\"\nrequird = false\ntype = \"any\"").contains("unknown field `requird`"));
+        assert!(error("[[fields]]\nkey = \"x\"\ntype = \"year\"\nmn = 1\nmax = 2").contains("unknown field `mn`"));
+        assert!(matches!(Schema::from_json(r#"{"fields": [{"key": "x", "requird": false, "type": "any"}]}"#),
+                         Err(SchemaError::Json(_))));
+
+        assert!(error("[[fields]]\nkey = \"x\"\ntype = \"year\"\nmin = 5\nmax = 3").contains("field x: empty year range 5..=3"));
+        let units = "[[fields]]\nkey = \"hgt\"\ntype = \"measurement\"\nunits = [{ suffix = \"cm\", min = 193, max = 150 }]";
+        assert!(error(units).contains("field hgt: empty cm range 193..=150"));
+
+        let schema = Schema::from_toml("[[fields]]\nkey = \"x\"\nrequired = false\ntype = \"year\"\nmin = 3\nmax = 3").unwrap();
+        assert_eq!(schema.fields[0], FieldSpec { key: "x".to_owned(), required: false, rule: Rule::Year { min: 3, max: 3 } });
+    }
+
+    #[test]
+    fn parse_crlf_and_blank_runs() {
+        let crlf = VALID.replace('\n', "\r\n").replace("\r\n\r\n", "\r\n\r\n  \r\n\r\n");
+        let schema = &Schema::puzzle();
+
+        let parsed = parse_with(&crlf, schema, Strictness::Strict).unwrap();
+        assert_eq!(parsed.records.len(), 4);
+        assert_eq!(count_valid(&parsed.records, |passport| validate_part_2(schema, passport)), 4);
+        assert_eq!(parsed.records[1].lines(), 6..=7);
+    }
+
+    #[test]
+    fn parse_field_positions() {
+        let passports = parse(VALID);
+        let field = passports[1].field("hcl").unwrap();
+
+        assert_eq!(field.value, "#a97842");
+        assert_eq!(field.position, Position { line: 5, column: 24 });
+    }
+
+    #[test]
+    fn parse_irregularities() {
+        let input = "byr:1920 foo:bar\nbyr:1921 iyr\n\n:2020";
+        let schema = &Schema::puzzle();
+
+        let parsed = parse_with(input, schema, Strictness::Lenient).unwrap();
+        let messages = parsed.diagnostics.iter().map(Diagnostic::to_string).collect::<Vec<_>>();
+        assert_eq!(messages, vec![
+            "warning: line 1, column 10: unknown key foo",
+            "warning: line 2, column 1: duplicate key byr (first at line 1, column 1)",
+            "warning: line 2, column 10: malformed token \"iyr\", expected key:value",
+            "warning: line 4, column 1: malformed token \":2020\", expected key:value",
+        ]);
+        assert_eq!(parsed.diagnostics[3].record, 1);
+        assert_eq!(parsed.records[0].get("byr"), Some("1921"));
+        assert_eq!(parsed.records[1].fields(), &[]);
+
+        let errors = parse_with(input, schema, Strictness::Strict).unwrap_err();
+        assert_eq!(errors.len(), 4);
+        assert!(errors.iter().all(|diagnostic| diagnostic.severity == Severity::Error));
+    }
+
+    /// Both parts from every record parsed at once, without streaming
+    fn solve_whole_input(input: &str) -> (usize, usize) {
+        let schema = &Schema::puzzle();
+        let passports = &parse(input);
+        (
+            count_valid(passports, |passport| validate_part_1(schema, passport)),
+            count_valid(passports, |passport| validate_part_2(schema, passport)),
+        )
+    }
+
+    #[test]
+    fn solve_reader_matches_whole_input() {
+        let schema = &Schema::puzzle();
+        assert_eq!(solve_reader(INPUT.as_bytes(), schema).unwrap(), solve_whole_input(INPUT));
+        assert_eq!(solve_input(INPUT), solve_whole_input(INPUT));
+        assert_eq!(solve_input(VALID), solve_whole_input(VALID));
+        assert_eq!(solve_input(INVALID), solve_whole_input(INVALID));
+
+        let crlf = INPUT.replace('\n', "\r\n").repeat(3);
+        assert_eq!(solve_reader(crlf.as_bytes(), schema).unwrap(), (237 * 3, 172 * 3));
+    }
+
+    #[test]
+    fn convert_round_trip() {
+        let schema = &Schema::puzzle();
+        let converter = Converter::new(schema);
+
+        for &format in &[Format::Csv, Format::JsonLines] {
+            let exported = converter.convert(INPUT, Format::Records, format).unwrap();
+            let records = converter.convert(&exported, format, Format::Records).unwrap();
+
+            assert_eq!(solve_input(&records), (237, 172));
+            assert_eq!(converter.convert(&records, Format::Records, format).unwrap(), exported);
+        }
+    }
+
+    #[test]
+    fn convert_normalizes_fields() {
+        let schema = &Schema::puzzle();
+        let converter = Converter::new(schema);
+        let input = "zzz:1 pid:1 iyr: byr:1\naaa:x,\"y byr:2\n\n\n\nhgt:1in";
+
+        assert_eq!(converter.convert(input, Format::Records, Format::Records).unwrap(),
+                   "byr:2 iyr: pid:1 aaa:x,\"y zzz:1\n\nhgt:1in\n");
+        assert_eq!(converter.convert(input, Format::Records, Format::Csv).unwrap(),
+                   "byr,iyr,eyr,hgt,hcl,ecl,pid,cid,aaa,zzz\n2,\"\",,,,,1,,\"x,\"\"y\",1\n,,,1in,,,,,,\n");
+        assert_eq!(converter.convert(input, Format::Records, Format::JsonLines).unwrap(),
+                   "{\"byr\":\"2\",\"iyr\":\"\",\"pid\":\"1\",\"aaa\":\"x,\\\"y\",\"zzz\":\"1\"}\n{\"hgt\":\"1in\"}\n");
+    }
+
+    #[test]
+    fn convert_only_valid() {
+        let schema = &Schema::puzzle();
+        let converter = Converter::new(schema).only_valid(true);
+
+        let csv = converter.convert(SAMPLE_01, Format::Records, Format::Csv).unwrap();
+        assert_eq!(csv.lines().count(), 3);
+
+        let records = converter.convert(&csv, Format::Csv, Format::Records).unwrap();
+        assert_eq!(solve_input(&records), (2, 2));
+    }
+
+    #[test]
+    fn convert_errors() {
+        let schema = &Schema::puzzle();
+        let converter = Converter::new(schema);
+
+        assert_eq!(converter.convert("byr,pid\n1920,\"12", Format::Csv, Format::Records),
+                   Err(ConvertError::Syntax { line: 2, message: "unterminated quoted cell".to_owned() }));
+        assert_eq!(converter.convert("byr,pid\n1920", Format::Csv, Format::Records),
+                   Err(ConvertError::Syntax { line: 2, message: "expected 2 cells, found 1".to_owned() }));
+        assert_eq!(converter.convert("{\"pid\":\"1 2\"}", Format::JsonLines, Format::Records),
+                   Err(ConvertError::InvalidField { line: 1, key: "pid".to_owned(), value: "1 2".to_owned() }));
+        assert!(matches!(converter.convert("{\"pid\":1}", Format::JsonLines, Format::Records),
+                         Err(ConvertError::Syntax { line: 1, .. })));
+        assert_eq!(converter.convert("byr:1\n\nfoo\n\niyr:2", Format::Records, Format::Records),
+                   Err(ConvertError::Syntax { line: 3, message: "malformed token \"foo\", expected key:value".to_owned() }));
+        assert!(matches!(converter.convert("byr:1 :2", Format::Records, Format::Csv),
+                         Err(ConvertError::Syntax { line: 1, .. })));
+    }
+
+    #[test]
+    fn convert_round_trip_keeps_record_count() {
+        let schema = &Schema::puzzle();
+        let converter = Converter::new(schema);
+        let count = |records: &str| parse_with(records, schema, Strictness::Lenient).unwrap().records.len();
+
+        let records = "byr:1\n\nzzz:3\n\niyr:2\n";
+        for &format in &[Format::Records, Format::Csv, Format::JsonLines] {
+            let exported = converter.convert(records, Format::Records, format).unwrap();
+            let back = converter.convert(&exported, format, Format::Records).unwrap();
+            assert_eq!(back, records);
+            assert_eq!(count(&back), 3);
+        }
+
+        // passports without fields have nothing to write, so are dropped in every format
+        assert_eq!(converter.convert("byr,iyr\n1,\n,\n,2", Format::Csv, Format::Records).unwrap(), "byr:1\n\niyr:2\n");
+        assert_eq!(converter.convert("{\"byr\":\"1\"}\n{}", Format::JsonLines, Format::Csv).unwrap(),
+                   "byr,iyr,eyr,hgt,hcl,ecl,pid,cid\n1,,,,,,,\n");
+    }
+
     #[cfg(all(feature = "unstable", test))]
     mod bench {
         extern crate test;
@@ -184,17 +494,19 @@ mod tests {
 
         #[bench]
         fn d04_validate_p1(b: &mut Bencher) {
+            let schema = &Schema::puzzle();
             let passports = &parse(INPUT);
             b.iter(|| {
-                count_valid(passports, crate::validate_part_1);
+                count_valid(passports, |passport| validate_part_1(schema, passport));
             });
         }
 
         #[bench]
         fn d04_validate_p2(b: &mut Bencher) {
+            let schema = &Schema::puzzle();
             let passports = &parse(INPUT);
             b.iter(|| {
-                count_valid(passports, crate::validate_part_2);
+                count_valid(passports, |passport| validate_part_2(schema, passport));
             });
         }
     }
@@ -0,0 +1,218 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::Schema;
+
+/// 1-based location of a token in the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// A single `key:value` pair and where it was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field<'a> {
+    pub key: &'a str,
+    pub value: &'a str,
+    pub position: Position,
+}
+
+/// A single passport record, the raw `key:value` pairs in the order they appear
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record<'a> {
+    fields: Vec<Field<'a>>,
+    lines: RangeInclusive<usize>,
+}
+
+impl<'a> Record<'a> {
+    /// Value of the field [key], later duplicates overwrite earlier ones
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        self.field(key).map(|field| field.value)
+    }
+
+    /// The field [key], later duplicates overwrite earlier ones
+    pub fn field(&self, key: &str) -> Option<&Field<'a>> {
+        self.fields.iter().rev().find(|field| field.key == key)
+    }
+
+    pub fn fields(&self) -> &[Field<'a>] {
+        &self.fields
+    }
+
+    /// The 1-based lines of the input this record spans
+    pub fn lines(&self) -> RangeInclusive<usize> {
+        self.lines.clone()
+    }
+}
+
+/// How irregularities in the input are reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strictness {
+    /// Irregularities are warnings, and parsing continues
+    Lenient,
+    /// Irregularities are errors, and parsing fails
+    Strict,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// The key was already present in the record, the later value wins
+    DuplicateKey { key: String, first: Position },
+    /// The key isn't part of the schema
+    UnknownKey { key: String },
+    /// The token isn't of the form `key:value`, it is skipped
+    MalformedToken { token: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// 0-based index of the record containing the issue
+    pub record: usize,
+    pub position: Position,
+    pub issue: Issue,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}: {}: ", severity, self.position)?;
+
+        match &self.issue {
+            Issue::DuplicateKey { key, first } => write!(f, "duplicate key {} (first at {})", key, first),
+            Issue::UnknownKey { key } => write!(f, "unknown key {}", key),
+            Issue::MalformedToken { token } => write!(f, "malformed token {:?}, expected key:value", token),
+        }
+    }
+}
+
+/// Records parsed from an input, along with any warnings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parsed<'a> {
+    pub records: Vec<Record<'a>>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Parses blank line separated records of whitespace separated `key:value` pairs.
+///
+/// Tolerates CRLF line endings and runs of blank lines. Duplicate keys, keys unknown to [schema]
+/// and tokens without a `:` are reported as warnings or errors according to [strictness], when
+/// strict any of them fails the whole parse.
+pub fn parse_with<'a>(input: &'a str, schema: &Schema, strictness: Strictness) -> Result<Parsed<'a>, Vec<Diagnostic>> {
+    let severity = match strictness {
+        Strictness::Lenient => Severity::Warning,
+        Strictness::Strict => Severity::Error,
+    };
+
+    let mut diagnostics = vec![];
+    let records = parse_records(
+        input,
+        |key| schema.fields.iter().any(|spec| spec.key == key),
+        |record, position, issue| diagnostics.push(Diagnostic { severity, record, position, issue }),
+    );
+
+    match strictness {
+        Strictness::Strict if !diagnostics.is_empty() => Err(diagnostics),
+        _ => Ok(Parsed { records, diagnostics }),
+    }
+}
+
+/// Parses every record, ignoring any irregularities
+pub(crate) fn parse(input: &str) -> Vec<Record<'_>> {
+    parse_records(input, |_| true, |_, _, _| {})
+}
+
+/// Parses every record, failing on the first token that isn't `key:value`
+pub(crate) fn parse_well_formed(input: &str) -> Result<Vec<Record<'_>>, (Position, String)> {
+    let mut malformed = None;
+    let records = parse_records(input, |_| true, |_, position, issue| {
+        if let (None, Issue::MalformedToken { token }) = (&malformed, issue) {
+            malformed = Some((position, token));
+        }
+    });
+
+    match malformed {
+        Some(malformed) => Err(malformed),
+        None => Ok(records),
+    }
+}
+
+/// Splits [input] into records, passing every irregularity to [on_issue]
+fn parse_records(
+    input: &str,
+    is_known: impl Fn(&str) -> bool,
+    mut on_issue: impl FnMut(usize, Position, Issue),
+) -> Vec<Record<'_>> {
+    let mut records = vec![];
+    let mut fields: Vec<Field> = vec![];
+    let mut first_line = 0;
+    let mut last_line = 0;
+
+    for (i, line) in input.lines().enumerate() {
+        let line_number = i + 1;
+
+        if line.trim().is_empty() {
+            if first_line != 0 {
+                records.push(Record { fields: std::mem::take(&mut fields), lines: first_line..=last_line });
+                first_line = 0;
+            }
+            continue;
+        }
+
+        if first_line == 0 {
+            first_line = line_number;
+        }
+        last_line = line_number;
+
+        let record = records.len();
+        for (offset, token) in tokens(line) {
+            let position = Position { line: line_number, column: offset + 1 };
+
+            match token.split_once(':') {
+                Some((key, value)) if !key.is_empty() => {
+                    if let Some(first) = fields.iter().find(|field| field.key == key) {
+                        on_issue(record, position, Issue::DuplicateKey { key: key.to_owned(), first: first.position });
+                    }
+                    if !is_known(key) {
+                        on_issue(record, position, Issue::UnknownKey { key: key.to_owned() });
+                    }
+                    fields.push(Field { key, value, position });
+                }
+                _ => on_issue(record, position, Issue::MalformedToken { token: token.to_owned() }),
+            }
+        }
+    }
+
+    if first_line != 0 {
+        records.push(Record { fields, lines: first_line..=last_line });
+    }
+
+    records
+}
+
+/// Whitespace separated tokens of [line], along with their byte offset
+fn tokens(line: &str) -> impl Iterator<Item=(usize, &str)> {
+    line.split(|c: char| c.is_ascii_whitespace())
+        .scan(0, |offset, token| {
+            let start = *offset;
+            *offset += token.len() + 1;
+            Some((start, token))
+        })
+        .filter(|(_, token)| !token.is_empty())
+}
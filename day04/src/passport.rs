@@ -0,0 +1,128 @@
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use crate::Record;
+
+/// Error returned when a single field value can't be parsed into its typed representation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseFieldError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Height {
+    Cm(u32),
+    In(u32),
+}
+
+impl FromStr for Height {
+    type Err = ParseFieldError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(cm) = s.strip_suffix("cm") {
+            cm.parse().map(Height::Cm).map_err(|_| ParseFieldError)
+        } else if let Some(inches) = s.strip_suffix("in") {
+            inches.parse().map(Height::In).map_err(|_| ParseFieldError)
+        } else {
+            Err(ParseFieldError)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HairColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl FromStr for HairColor {
+    type Err = ParseFieldError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.strip_prefix('#')
+            .filter(|hex| hex.len() == 6 && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or(ParseFieldError)?;
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| ParseFieldError);
+
+        Ok(HairColor { r: channel(0)?, g: channel(2)?, b: channel(4)? })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EyeColor {
+    Amb,
+    Blu,
+    Brn,
+    Gry,
+    Grn,
+    Hzl,
+    Oth,
+}
+
+impl FromStr for EyeColor {
+    type Err = ParseFieldError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "amb" => Ok(EyeColor::Amb),
+            "blu" => Ok(EyeColor::Blu),
+            "brn" => Ok(EyeColor::Brn),
+            "gry" => Ok(EyeColor::Gry),
+            "grn" => Ok(EyeColor::Grn),
+            "hzl" => Ok(EyeColor::Hzl),
+            "oth" => Ok(EyeColor::Oth),
+            _ => Err(ParseFieldError),
+        }
+    }
+}
+
+/// A passport with every field parsed into its typed representation.
+///
+/// Parsing only checks the shape of each value, range constraints are the job of a [crate::Schema].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Passport<'a> {
+    pub byr: u32,
+    pub iyr: u32,
+    pub eyr: u32,
+    pub hgt: Height,
+    pub hcl: HairColor,
+    pub ecl: EyeColor,
+    pub pid: &'a str,
+    pub cid: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PassportError {
+    Missing(&'static str),
+    Invalid(&'static str),
+}
+
+impl<'a> TryFrom<&Record<'a>> for Passport<'a> {
+    type Error = PassportError;
+
+    fn try_from(record: &Record<'a>) -> Result<Self, Self::Error> {
+        fn field<T: FromStr>(record: &Record, key: &'static str) -> Result<T, PassportError> {
+            record.get(key)
+                .ok_or(PassportError::Missing(key))?
+                .parse()
+                .map_err(|_| PassportError::Invalid(key))
+        }
+
+        Ok(Passport {
+            byr: field(record, "byr")?,
+            iyr: field(record, "iyr")?,
+            eyr: field(record, "eyr")?,
+            hgt: field(record, "hgt")?,
+            hcl: field(record, "hcl")?,
+            ecl: field(record, "ecl")?,
+            pid: record.get("pid")
+                .ok_or(PassportError::Missing("pid"))
+                .and_then(|pid| if pid.bytes().all(|b| b.is_ascii_digit()) {
+                    Ok(pid)
+                } else {
+                    Err(PassportError::Invalid("pid"))
+                })?,
+            cid: record.get("cid"),
+        })
+    }
+}
@@ -0,0 +1,92 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::{Record, Schema, Violation};
+
+/// Validation result of a single record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordReport {
+    /// 0-based position of the record in the input
+    pub index: usize,
+    /// 1-based lines of the input the record spans
+    pub lines: RangeInclusive<usize>,
+    pub violations: Vec<Violation>,
+}
+
+impl RecordReport {
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Per-field diagnostics for a batch of passports, printable as text or serializable as JSON
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report(pub Vec<RecordReport>);
+
+impl Report {
+    pub fn new(records: &[Record], schema: &Schema) -> Report {
+        Report(records.iter().enumerate()
+            .map(|(index, record)| RecordReport {
+                index,
+                lines: record.lines(),
+                violations: schema.violations(record),
+            })
+            .collect())
+    }
+
+    pub fn valid_count(&self) -> usize {
+        self.0.iter().filter(|record| record.is_valid()).count()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report is always serializable")
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for record in &self.0 {
+            let status = if record.is_valid() { "valid" } else { "invalid" };
+            writeln!(f, "record {} (lines {}-{}): {}",
+                     record.index, record.lines.start(), record.lines.end(), status)?;
+            for violation in &record.violations {
+                writeln!(f, "    {}", violation)?;
+            }
+        }
+        write!(f, "{} of {} valid", self.valid_count(), self.0.len())
+    }
+}
+
+impl Serialize for Report {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Report", 3)?;
+        state.serialize_field("records", &self.0)?;
+        state.serialize_field("valid", &self.valid_count())?;
+        state.serialize_field("total", &self.0.len())?;
+        state.end()
+    }
+}
+
+impl Serialize for RecordReport {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("RecordReport", 5)?;
+        state.serialize_field("index", &self.index)?;
+        state.serialize_field("first_line", self.lines.start())?;
+        state.serialize_field("last_line", self.lines.end())?;
+        state.serialize_field("valid", &self.is_valid())?;
+        state.serialize_field("violations", &self.violations)?;
+        state.end()
+    }
+}
+
+impl Serialize for Violation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Violation", 3)?;
+        state.serialize_field("field", &self.key)?;
+        state.serialize_field("value", &self.value)?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
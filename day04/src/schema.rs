@@ -0,0 +1,399 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::Record;
+
+/// A unit suffix accepted by [Rule::Measurement], along with its inclusive range
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Unit {
+    pub suffix: String,
+    pub min: u32,
+    pub max: u32,
+}
+
+/// A regular expression that must match the whole value
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Pattern, regex::Error> {
+        Regex::new(&format!("^(?:{})$", pattern)).map(Pattern)
+    }
+
+    pub fn is_match(&self, value: &str) -> bool {
+        self.0.is_match(value)
+    }
+
+    /// The pattern as written, without the implicit anchors
+    pub fn as_str(&self) -> &str {
+        let anchored = self.0.as_str();
+        &anchored[4..anchored.len() - 2]
+    }
+}
+
+impl TryFrom<String> for Pattern {
+    type Error = regex::Error;
+
+    fn try_from(pattern: String) -> Result<Self, Self::Error> {
+        Pattern::new(&pattern)
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Pattern {}
+
+/// Constraint on the value of a single field
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Rule {
+    /// Any value is accepted
+    Any,
+    /// An integer year within `min..=max`
+    Year { min: u32, max: u32 },
+    /// An integer immediately followed by one of the unit suffixes, within that unit's range
+    Measurement { units: Vec<Unit> },
+    /// `#` followed by exactly six hex digits
+    HexColor,
+    /// The whole value matches a regular expression
+    Pattern { pattern: Pattern },
+    /// Exactly one of the listed values
+    OneOf { values: Vec<String> },
+    /// Exactly [length] ascii digits, leading zeroes included
+    Digits { length: usize },
+}
+
+impl Rule {
+    /// The keys this rule is written with, besides `type`
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            Rule::Any | Rule::HexColor => &[],
+            Rule::Year { .. } => &["min", "max"],
+            Rule::Measurement { .. } => &["units"],
+            Rule::Pattern { .. } => &["pattern"],
+            Rule::OneOf { .. } => &["values"],
+            Rule::Digits { .. } => &["length"],
+        }
+    }
+
+    /// Rejects ranges with `min > max`, which no value could fall in
+    fn check_ranges(&self) -> Result<(), String> {
+        let check = |name: &str, min: u32, max: u32| {
+            if min <= max { Ok(()) } else { Err(format!("empty {} range {}..={}", name, min, max)) }
+        };
+
+        match self {
+            Rule::Year { min, max } => check("year", *min, *max),
+            Rule::Measurement { units } => {
+                units.iter().try_for_each(|unit| check(&unit.suffix, unit.min, unit.max))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn matches(&self, value: &str) -> bool {
+        self.check(value).is_ok()
+    }
+
+    /// Checks [value] against this rule, returning the reason it was rejected
+    pub fn check(&self, value: &str) -> Result<(), Reason> {
+        fn number(value: &str) -> Result<u32, Reason> {
+            value.parse().map_err(|_| Reason::NotANumber)
+        }
+
+        fn in_range(n: u32, min: u32, max: u32) -> Result<(), Reason> {
+            if (min..=max).contains(&n) { Ok(()) } else { Err(Reason::OutOfRange { min, max }) }
+        }
+
+        match self {
+            Rule::Any => Ok(()),
+            Rule::Year { min, max } => in_range(number(value)?, *min, *max),
+            Rule::Measurement { units } => {
+                let (n, unit) = units.iter()
+                    .find_map(|unit| value.strip_suffix(unit.suffix.as_str()).map(|n| (n, unit)))
+                    .ok_or_else(|| Reason::UnknownUnit {
+                        expected: units.iter().map(|unit| unit.suffix.clone()).collect()
+                    })?;
+                in_range(number(n)?, unit.min, unit.max)
+            }
+            Rule::HexColor => {
+                let hex = value.strip_prefix('#').ok_or(Reason::MissingHash)?;
+                if hex.len() == 6 && hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    Ok(())
+                } else {
+                    Err(Reason::NotHexColor)
+                }
+            }
+            Rule::Pattern { pattern } => {
+                if pattern.is_match(value) {
+                    Ok(())
+                } else {
+                    Err(Reason::NoMatch { pattern: pattern.as_str().to_owned() })
+                }
+            }
+            Rule::OneOf { values } => {
+                if values.iter().any(|it| it == value) {
+                    Ok(())
+                } else {
+                    Err(Reason::NotOneOf { expected: values.clone() })
+                }
+            }
+            Rule::Digits { length } => {
+                if !value.bytes().all(|b| b.is_ascii_digit()) {
+                    Err(Reason::NotDigits)
+                } else if value.len() != *length {
+                    Err(Reason::WrongLength { expected: *length, actual: value.len() })
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+/// Why a field failed validation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reason {
+    Missing,
+    NotANumber,
+    OutOfRange { min: u32, max: u32 },
+    UnknownUnit { expected: Vec<String> },
+    MissingHash,
+    NotHexColor,
+    NoMatch { pattern: String },
+    NotOneOf { expected: Vec<String> },
+    NotDigits,
+    WrongLength { expected: usize, actual: usize },
+}
+
+/// A single field of a record that failed validation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub key: String,
+    pub value: Option<String>,
+    pub reason: Reason,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key = &self.key;
+        let value = self.value.as_deref().unwrap_or_default();
+
+        match &self.reason {
+            Reason::Missing => write!(f, "{}: missing", key),
+            Reason::NotANumber => write!(f, "{}: {} is not a number", key, value),
+            Reason::OutOfRange { min, max } => write!(f, "{}: {} out of range {}..={}", key, value, min, max),
+            Reason::UnknownUnit { expected } => {
+                let problem = if value.bytes().all(|b| b.is_ascii_digit()) { "has no unit" } else { "unit not accepted" };
+                write!(f, "{}: {} {}, expected one of {}", key, value, problem, expected.join(", "))
+            }
+            Reason::MissingHash => write!(f, "{}: missing '#'", key),
+            Reason::NotHexColor => write!(f, "{}: {} is not 6 hex digits", key, value),
+            Reason::NoMatch { pattern } => write!(f, "{}: {} does not match {}", key, value, pattern),
+            Reason::NotOneOf { expected } => {
+                write!(f, "{}: {} is not one of {}", key, value, expected.join(", "))
+            }
+            Reason::NotDigits => write!(f, "{}: {} contains non-digits", key, value),
+            Reason::WrongLength { expected, actual } => {
+                write!(f, "{}: expected {} digits, found {}", key, expected, actual)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawFieldSpec")]
+pub struct FieldSpec {
+    pub key: String,
+    pub required: bool,
+    pub rule: Rule,
+}
+
+/// A [FieldSpec] as written, with its rule's keys next to `key` and `required`.
+///
+/// Flattening the [Rule] straight into [FieldSpec] would let unknown keys through, so a mistyped
+/// `required` would quietly leave the field required. Instead every other key is gathered, the
+/// rule is read from them and any key the rule doesn't use is rejected.
+#[derive(Deserialize)]
+struct RawFieldSpec {
+    key: String,
+    #[serde(default = "FieldSpec::required_by_default")]
+    required: bool,
+    #[serde(flatten)]
+    rule: serde_json::Map<String, serde_json::Value>,
+}
+
+impl TryFrom<RawFieldSpec> for FieldSpec {
+    type Error = String;
+
+    fn try_from(raw: RawFieldSpec) -> Result<Self, Self::Error> {
+        let RawFieldSpec { key, required, rule: keys } = raw;
+        let rule = Rule::deserialize(serde_json::Value::Object(keys.clone()))
+            .map_err(|e| format!("field {}: {}", key, e))?;
+
+        if let Some(unknown) = keys.keys().find(|&k| k != "type" && !rule.keys().contains(&k.as_str())) {
+            return Err(format!("field {}: unknown field `{}`", key, unknown));
+        }
+        rule.check_ranges().map_err(|e| format!("field {}: {}", key, e))?;
+        Ok(FieldSpec { key, required, rule })
+    }
+}
+
+impl FieldSpec {
+    fn required(key: &str, rule: Rule) -> FieldSpec {
+        FieldSpec { key: key.to_owned(), required: true, rule }
+    }
+
+    fn optional(key: &str, rule: Rule) -> FieldSpec {
+        FieldSpec { key: key.to_owned(), required: false, rule }
+    }
+
+    fn required_by_default() -> bool {
+        true
+    }
+}
+
+/// Declarative description of a valid passport, one [FieldSpec] per known field
+///
+/// Rule sets can be loaded from TOML or JSON, e.g.
+///
+/// ```toml
+/// [[fields]]
+/// key = "hgt"
+/// type = "measurement"
+/// units = [{ suffix = "cm", min = 150, max = 193 }]
+///
+/// [[fields]]
+/// key = "cid"
+/// required = false
+/// type = "digits"
+/// length = 3
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schema {
+    pub fields: Vec<FieldSpec>,
+}
+
+impl Schema {
+    /// The rules from the puzzle text
+    pub fn puzzle() -> Schema {
+        let unit = |suffix: &str, min, max| Unit { suffix: suffix.to_owned(), min, max };
+        let eye_colors = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+        Schema {
+            fields: vec![
+                FieldSpec::required("byr", Rule::Year { min: 1920, max: 2002 }),
+                FieldSpec::required("iyr", Rule::Year { min: 2010, max: 2020 }),
+                FieldSpec::required("eyr", Rule::Year { min: 2020, max: 2030 }),
+                FieldSpec::required("hgt", Rule::Measurement {
+                    units: vec![unit("cm", 150, 193), unit("in", 59, 76)]
+                }),
+                FieldSpec::required("hcl", Rule::HexColor),
+                FieldSpec::required("ecl", Rule::OneOf {
+                    values: eye_colors.iter().map(|&it| it.to_owned()).collect()
+                }),
+                FieldSpec::required("pid", Rule::Digits { length: 9 }),
+                FieldSpec::optional("cid", Rule::Any),
+            ]
+        }
+    }
+
+    pub fn from_toml(input: &str) -> Result<Schema, SchemaError> {
+        toml::from_str(input).map_err(SchemaError::Toml)
+    }
+
+    pub fn from_json(input: &str) -> Result<Schema, SchemaError> {
+        serde_json::from_str(input).map_err(SchemaError::Json)
+    }
+
+    /// Loads a rule set, the format is chosen by the file extension (`.toml` or `.json`)
+    pub fn load(path: impl AsRef<Path>) -> Result<Schema, SchemaError> {
+        let path = path.as_ref();
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Schema::from_toml(&fs::read_to_string(path).map_err(SchemaError::Io)?),
+            Some("json") => Schema::from_json(&fs::read_to_string(path).map_err(SchemaError::Io)?),
+            _ => Err(SchemaError::UnknownFormat(path.display().to_string())),
+        }
+    }
+
+    /// true if every required field is present, regardless of its value
+    pub fn has_required_fields(&self, record: &Record) -> bool {
+        self.fields.iter()
+            .filter(|spec| spec.required)
+            .all(|spec| record.get(&spec.key).is_some())
+    }
+
+    /// true if every required field is present, and every present field satisfies its rule
+    pub fn validate(&self, record: &Record) -> bool {
+        self.fields.iter().all(|spec| Self::check_field(spec, record).is_ok())
+    }
+
+    /// Every field of [record] that fails validation, in schema order
+    pub fn violations(&self, record: &Record) -> Vec<Violation> {
+        self.fields.iter()
+            .filter_map(|spec| {
+                Self::check_field(spec, record).err().map(|reason| Violation {
+                    key: spec.key.clone(),
+                    value: record.get(&spec.key).map(str::to_owned),
+                    reason,
+                })
+            })
+            .collect()
+    }
+
+    fn check_field(spec: &FieldSpec, record: &Record) -> Result<(), Reason> {
+        match record.get(&spec.key) {
+            Some(value) => spec.rule.check(value),
+            None if spec.required => Err(Reason::Missing),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for Schema {
+    fn default() -> Self {
+        Schema::puzzle()
+    }
+}
+
+#[derive(Debug)]
+pub enum SchemaError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+    UnknownFormat(String),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::Io(e) => write!(f, "failed to read rule set: {}", e),
+            SchemaError::Toml(e) => write!(f, "invalid TOML rule set: {}", e),
+            SchemaError::Json(e) => write!(f, "invalid JSON rule set: {}", e),
+            SchemaError::UnknownFormat(path) => write!(f, "{}: expected a .toml or .json rule set", path),
+        }
+    }
+}
+
+impl Error for SchemaError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SchemaError::Io(e) => Some(e),
+            SchemaError::Toml(e) => Some(e),
+            SchemaError::Json(e) => Some(e),
+            SchemaError::UnknownFormat(_) => None,
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Branch-free decoding for layouts with a power of two columns, where a pass is just the seat id
+//! written in binary: `F`/`L` are 0 and `B`/`R` are 1.
+//!
+//! `F` (0x46) and `L` (0x4c) have bit 2 set, `B` (0x42) and `R` (0x52) don't, so each character's
+//! bit is its inverted bit 2. Eight characters at a time are loaded into a u64, masked down to
+//! that bit, and gathered into a single byte with one multiply.
+
+use std::convert::TryInto;
+
+/// Bit 2 of every byte
+const LANES: u64 = 0x0404_0404_0404_0404;
+
+/// Moves bit 0 of byte `i` to bit `63 - i`, no two partial products overlap so nothing carries
+const GATHER: u64 = 0x8040_2010_0804_0201;
+
+#[inline]
+fn bit(c: u8) -> usize {
+    (!c >> 2 & 1) as usize
+}
+
+#[inline]
+fn gather(chunk: [u8; 8]) -> usize {
+    let bits = (!u64::from_le_bytes(chunk) & LANES) >> 2;
+    (bits.wrapping_mul(GATHER) >> 56) as usize
+}
+
+/// Decodes a single pass, [pass] must only contain `F`, `B`, `L` and `R`
+#[inline]
+pub fn decode(pass: &[u8]) -> usize {
+    let mut chunks = pass.chunks_exact(8);
+    let mut seat_id = 0;
+
+    for chunk in &mut chunks {
+        seat_id = seat_id << 8 | gather(chunk.try_into().unwrap());
+    }
+    for &c in chunks.remainder() {
+        seat_id = seat_id << 1 | bit(c);
+    }
+
+    seat_id
+}
+
+/// Decodes every pass in [input], one per line.
+///
+/// When every line is exactly [pass_len] characters followed by `\n`, the last line's `\n` being
+/// optional, the buffer is strided over without searching for line breaks. Any other input, like
+/// CRLF line endings, blank lines or stray whitespace, goes through [str::lines] with every line
+/// trimmed and blank lines skipped.
+pub fn decode_all(input: &str, pass_len: usize) -> impl Iterator<Item=usize> + '_ {
+    let aligned = is_aligned(input.as_bytes(), pass_len);
+
+    let strided = if aligned { Some(input.as_bytes().chunks(pass_len + 1)) } else { None };
+    let lines = if aligned { None } else { Some(input.lines()) };
+
+    let strided = strided.into_iter().flatten().map(move |line| decode(&line[..pass_len]));
+    let lines = lines.into_iter()
+        .flatten()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| decode(line.as_bytes()));
+    strided.chain(lines)
+}
+
+/// Whether [input] is lines of exactly [pass_len] characters, every one ending in `\n` except
+/// maybe the last. Besides a `\n` at every `pass_len + 1` bytes there can't be any other, which
+/// would mean a line shorter than a pass, such as a truncated last one.
+fn is_aligned(input: &[u8], pass_len: usize) -> bool {
+    let stride = pass_len + 1;
+    let newlines = input.len() / stride;
+    let last_line = input.len() % stride;
+    let whole_lines = last_line == 0 || last_line == pass_len;
+
+    whole_lines
+        && input.iter().skip(pass_len).step_by(stride).all(|&c| c == b'\n')
+        && input.iter().filter(|&&c| c == b'\n').count() == newlines
+}
+
+/// XOR of every integer in `0..=n`
+fn xor_upto(n: usize) -> usize {
+    match n % 4 {
+        0 => n,
+        1 => 1,
+        2 => n + 1,
+        _ => 0,
+    }
+}
+
+/// Highest seat id, and the single seat missing between the lowest and highest.
+///
+/// `None` if there are no seats, a seat is taken twice, or there isn't exactly one gap. With one
+/// seat missing the range `min..=max` holds one more seat than were decoded, so a presence bitset
+/// of that size catches duplicates, and XORing every seat id in the range with every decoded seat
+/// id cancels out all but the missing one.
+pub fn max_and_missing(seat_ids: impl Iterator<Item=usize>) -> Option<(usize, usize)> {
+    let seat_ids = seat_ids.collect::<Vec<_>>();
+    let min = *seat_ids.iter().min()?;
+    let max = *seat_ids.iter().max()?;
+
+    if max - min != seat_ids.len() {
+        return None;
+    }
+
+    let mut seen = vec![0u64; (max - min) / 64 + 1];
+    for &seat_id in &seat_ids {
+        let (word, bit) = ((seat_id - min) / 64, 1 << ((seat_id - min) % 64));
+        if seen[word] & bit != 0 {
+            return None;
+        }
+        seen[word] |= bit;
+    }
+
+    let xor = seat_ids.iter().fold(0, |xor, seat_id| xor ^ seat_id);
+    let expected = xor_upto(max) ^ if min == 0 { 0 } else { xor_upto(min - 1) };
+    Some((max, expected ^ xor))
+}
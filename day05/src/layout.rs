@@ -0,0 +1,198 @@
+use std::error::Error;
+use std::fmt;
+
+/// Dimensions of an aircraft's seating, and the binary space partitioning used by its boarding passes.
+///
+/// A pass is the row, encoded with `F` (lower half) and `B` (upper half), followed by the column,
+/// encoded with `L` and `R`. Each part uses just enough characters to address every row or column,
+/// so layouts that aren't a power of two leave some codes unused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SeatLayout {
+    rows: usize,
+    cols: usize,
+}
+
+impl SeatLayout {
+    /// The 128 row, 8 column layout from the puzzle
+    pub const PUZZLE: SeatLayout = SeatLayout { rows: 128, cols: 8 };
+
+    pub fn new(rows: usize, cols: usize) -> SeatLayout {
+        assert!(rows > 0 && cols > 0, "a seat layout needs at least one row and one column");
+        SeatLayout { rows, cols }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Number of `F`/`B` characters in a pass
+    pub fn row_bits(&self) -> usize {
+        bits_for(self.rows)
+    }
+
+    /// Number of `L`/`R` characters in a pass
+    pub fn col_bits(&self) -> usize {
+        bits_for(self.cols)
+    }
+
+    pub fn pass_len(&self) -> usize {
+        self.row_bits() + self.col_bits()
+    }
+
+    /// Total number of seats, every seat id is less than this
+    pub fn seat_count(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    pub fn seat_id(&self, row: usize, col: usize) -> usize {
+        row * self.cols + col
+    }
+
+    /// (row, col) of [seat_id]
+    pub fn seat(&self, seat_id: usize) -> (usize, usize) {
+        (seat_id / self.cols, seat_id % self.cols)
+    }
+
+    /// Decodes the seat id of [pass], characters other than `F`/`B`/`L`/`R` are ignored
+    pub fn decode(&self, pass: &str) -> usize {
+        let mut chars = pass.chars();
+        let row = decode_binary_space(chars.by_ref().take(self.row_bits()), 'F', 'B', (1 << self.row_bits()) - 1);
+        let col = decode_binary_space(chars, 'L', 'R', (1 << self.col_bits()) - 1);
+        self.seat_id(row, col)
+    }
+
+    /// Decodes the seat id of [pass], rejecting anything but a well formed pass for a seat of this layout
+    pub fn try_decode(&self, pass: &str) -> Result<usize, DecodeError> {
+        let actual = pass.chars().count();
+        if actual != self.pass_len() {
+            return Err(DecodeError::WrongLength { expected: self.pass_len(), actual });
+        }
+
+        let mut row = 0;
+        let mut col = 0;
+        for (i, c) in pass.chars().enumerate() {
+            let column = i + 1;
+            let is_row = i < self.row_bits();
+
+            match (c, is_row) {
+                ('F', true) => row <<= 1,
+                ('B', true) => row = row << 1 | 1,
+                ('L', false) => col <<= 1,
+                ('R', false) => col = col << 1 | 1,
+                ('F', false) | ('B', false) | ('L', true) | ('R', true) => {
+                    return Err(DecodeError::WrongHalf { column, found: c });
+                }
+                _ => return Err(DecodeError::InvalidChar { column, found: c }),
+            }
+        }
+
+        if row >= self.rows || col >= self.cols {
+            return Err(DecodeError::OutsideLayout { row, col });
+        }
+
+        Ok(self.seat_id(row, col))
+    }
+
+    /// Strictly decodes one pass per line of [input], errors carry the 1-based line number
+    pub fn try_decode_lines<'a>(&'a self, input: &'a str) -> impl Iterator<Item=Result<usize, PassError>> + 'a {
+        input.lines().enumerate()
+            .map(move |(i, pass)| self.try_decode(pass).map_err(|error| PassError { line: i + 1, error }))
+    }
+
+    /// Encodes [seat_id] as a boarding pass, `None` if the seat isn't part of this layout
+    pub fn encode(&self, seat_id: usize) -> Option<String> {
+        if seat_id >= self.seat_count() {
+            return None;
+        }
+
+        let (row, col) = self.seat(seat_id);
+        let mut pass = String::with_capacity(self.pass_len());
+        encode_binary_space(&mut pass, row, self.row_bits(), 'F', 'B');
+        encode_binary_space(&mut pass, col, self.col_bits(), 'L', 'R');
+        Some(pass)
+    }
+}
+
+impl Default for SeatLayout {
+    fn default() -> Self {
+        SeatLayout::PUZZLE
+    }
+}
+
+/// Bits needed to address [n] distinct values
+fn bits_for(n: usize) -> usize {
+    n.next_power_of_two().trailing_zeros() as usize
+}
+
+fn decode_binary_space(input: impl Iterator<Item=char>, lo: char, hi: char, r: usize) -> usize {
+    let mut r = r;
+    let mut l = 0;
+
+    for c in input {
+        let m = (l + r) / 2;
+        if c == lo { r = m } else if c == hi { l = m + 1 };
+    }
+
+    l
+}
+
+fn encode_binary_space(out: &mut String, n: usize, bits: usize, lo: char, hi: char) {
+    for bit in (0..bits).rev() {
+        out.push(if n >> bit & 1 == 0 { lo } else { hi });
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    WrongLength { expected: usize, actual: usize },
+    /// Not one of `F`, `B`, `L` or `R`, [column] is 1-based
+    InvalidChar { column: usize, found: char },
+    /// A row letter in the column part, or a column letter in the row part
+    WrongHalf { column: usize, found: char },
+    /// Well formed, but past the last row or column of a layout that isn't a power of two
+    OutsideLayout { row: usize, col: usize },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::WrongLength { expected, actual } => {
+                write!(f, "expected {} characters, found {}", expected, actual)
+            }
+            DecodeError::InvalidChar { column, found } => {
+                write!(f, "column {}: invalid character {:?}, expected one of F, B, L, R", column, found)
+            }
+            DecodeError::WrongHalf { column, found } => {
+                let expected = if *found == 'F' || *found == 'B' { "L or R" } else { "F or B" };
+                write!(f, "column {}: found {:?} where {} was expected", column, found, expected)
+            }
+            DecodeError::OutsideLayout { row, col } => write!(f, "seat {}, {} is outside the layout", row, col),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+/// A [DecodeError] in a file of boarding passes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PassError {
+    /// 1-based line of the pass
+    pub line: usize,
+    pub error: DecodeError,
+}
+
+impl fmt::Display for PassError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl Error for PassError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
@@ -1,32 +1,29 @@
 #![cfg_attr(feature = "unstable", feature(test))]
 
-const INPUT: &'static str = include_str!("../input");
+pub use crate::layout::{DecodeError, PassError, SeatLayout};
+pub use crate::seatmap::{Duplicate, EmptySeat, Occupancy, SeatMap, Style, Vacancy};
 
-fn decode_binary_space(input: &str, lo: char, hi: char, r: usize) -> usize {
-    let mut r = r;
-    let mut l = 0;
+mod fast;
+mod layout;
+mod seatmap;
 
-    for c in input.chars() {
-        let m = (l + (r - 1)) / 2;
-        if c == lo { r = m } else if c == hi { l = m + 1 };
-    }
+const INPUT: &'static str = include_str!("../input");
 
-    l
+fn decode(input: &str) -> usize {
+    SeatLayout::PUZZLE.decode(input)
 }
 
-fn calc_seat_id(row: usize, col: usize) -> usize {
-    row * 8 + col
+pub fn solve() -> (usize, usize) {
+    solve_input(INPUT)
 }
 
-fn decode(input: &str) -> usize {
-    let (enc_row, enc_col) = input.split_at(7);
-    let row = decode_binary_space(enc_row, 'F', 'B', 127);
-    let col = decode_binary_space(enc_col, 'L', 'R', 7);
-    let seat_id = calc_seat_id(row, col);
-    seat_id
+pub fn solve_input(input: &str) -> (usize, usize) {
+    fast::max_and_missing(fast::decode_all(input, SeatLayout::PUZZLE.pass_len()))
+        .expect("expected exactly one missing seat between the lowest and highest")
 }
 
-pub fn solve() -> (usize, usize) {
+/// The original sort based solution, kept to check [solve] against
+pub fn solve_reference() -> (usize, usize) {
     let mut seat_ids = INPUT.lines().map(crate::decode).collect::<Vec<usize>>();
     seat_ids.sort_unstable();
 
@@ -83,6 +80,213 @@ mod tests {
         assert_eq!(actual, 579);
     }
 
+    #[test]
+    fn encode() {
+        let passes = SAMPLE_01.lines().collect::<Vec<_>>();
+        let encoded = [357, 567, 119, 820].iter()
+            .map(|&seat_id| SeatLayout::PUZZLE.encode(seat_id).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(encoded, passes);
+        assert_eq!(SeatLayout::PUZZLE.encode(1024), None);
+    }
+
+    #[test]
+    fn layout_dimensions() {
+        let layout = SeatLayout::new(100, 10);
+        assert_eq!(layout.row_bits(), 7);
+        assert_eq!(layout.col_bits(), 4);
+        assert_eq!(layout.seat_count(), 1000);
+        assert_eq!(layout.seat(357), (35, 7));
+        assert_eq!(layout.encode(357).unwrap(), "FBFFFBBLRRR");
+
+        let single = SeatLayout::new(1, 1);
+        assert_eq!(single.pass_len(), 0);
+        assert_eq!(single.encode(0).unwrap(), "");
+        assert_eq!(single.decode(""), 0);
+    }
+
+    #[test]
+    fn round_trip_all_seats() {
+        let layouts = [
+            SeatLayout::PUZZLE,
+            SeatLayout::new(1, 1),
+            SeatLayout::new(1, 6),
+            SeatLayout::new(3, 5),
+            SeatLayout::new(33, 4),
+            SeatLayout::new(100, 10),
+            SeatLayout::new(256, 16),
+        ];
+
+        for layout in &layouts {
+            for seat_id in 0..layout.seat_count() {
+                let pass = layout.encode(seat_id).unwrap();
+                assert_eq!(pass.len(), layout.pass_len());
+                assert_eq!(layout.decode(&pass), seat_id, "{:?} {}", layout, pass);
+            }
+        }
+    }
+
+    #[test]
+    fn occupancy_puzzle_input() {
+        let occupancy = SeatMap::from_passes(SeatLayout::PUZZLE, INPUT).occupancy();
+
+        let interior = occupancy.interior().map(|seat| seat.seat_id).collect::<Vec<_>>();
+        assert_eq!(interior, vec![579]);
+        assert!(occupancy.empty.iter().all(|seat| seat.vacancy != Vacancy::Front || seat.seat_id < 579));
+        assert!(occupancy.empty.iter().all(|seat| seat.vacancy != Vacancy::Back || seat.seat_id > 894));
+        assert_eq!(occupancy.empty.len(), SeatLayout::PUZZLE.seat_count() - INPUT.lines().count());
+        assert_eq!(occupancy.duplicates, vec![]);
+        assert_eq!(occupancy.malformed, vec![]);
+    }
+
+    #[test]
+    fn occupancy_skips_blank_and_malformed_passes() {
+        let layout = SeatLayout::new(4, 2);
+        let occupancy = SeatMap::from_passes(layout, "FBR\nBFL\n\nFXR\r\nBF\n").occupancy();
+
+        assert_eq!(occupancy.malformed, vec![
+            PassError { line: 4, error: DecodeError::InvalidChar { column: 2, found: 'X' } },
+            PassError { line: 5, error: DecodeError::WrongLength { expected: 3, actual: 2 } },
+        ]);
+        assert_eq!(occupancy.duplicates, vec![]);
+        assert_eq!(occupancy.outside, vec![]);
+
+        let empty = occupancy.empty.iter().map(|seat| (seat.seat_id, seat.vacancy)).collect::<Vec<_>>();
+        assert_eq!(empty, vec![
+            (0, Vacancy::Front),
+            (1, Vacancy::Front),
+            (2, Vacancy::Front),
+            (5, Vacancy::Back),
+            (6, Vacancy::Back),
+            (7, Vacancy::Back),
+        ]);
+    }
+
+    #[test]
+    fn occupancy_duplicates_and_outside() {
+        let layout = SeatLayout::new(3, 2);
+        let input = "FFR\nFBL\nFFR\nBBL\nFFR";
+        let occupancy = SeatMap::from_passes(layout, input).occupancy();
+
+        assert_eq!(occupancy.duplicates, vec![Duplicate { seat_id: 1, lines: vec![1, 3, 5] }]);
+        assert_eq!(occupancy.outside, vec![4]);
+        assert_eq!(occupancy.empty, vec![
+            EmptySeat { seat_id: 0, row: 0, col: 0, vacancy: Vacancy::Front },
+            EmptySeat { seat_id: 3, row: 1, col: 1, vacancy: Vacancy::Back },
+            EmptySeat { seat_id: 4, row: 2, col: 0, vacancy: Vacancy::Back },
+            EmptySeat { seat_id: 5, row: 2, col: 1, vacancy: Vacancy::Back },
+        ]);
+    }
+
+    #[test]
+    fn render_seat_map() {
+        let layout = SeatLayout::new(4, 3);
+        let input = "FBLL\nFBLR\nBFLL\nFBLL\nFFLR";
+        let map = SeatMap::from_passes(layout, input);
+
+        assert_eq!(map.render(Style::Ascii), "0 .#O\n1 2#O\n2 #..\n3 ...\n");
+        assert_eq!(map.render(Style::Ansi).lines().nth(1).unwrap(),
+                   "1 \x1b[1;33m2\x1b[0m#\x1b[1;41mO\x1b[0m");
+    }
+
+    #[test]
+    fn try_decode() {
+        let layout = SeatLayout::PUZZLE;
+
+        assert_eq!(layout.try_decode("FBFBBFFRLR"), Ok(357));
+        assert_eq!(layout.try_decode("FBFBBFF"), Err(DecodeError::WrongLength { expected: 10, actual: 7 }));
+        assert_eq!(layout.try_decode("FBXBBFFRRL"), Err(DecodeError::InvalidChar { column: 3, found: 'X' }));
+        assert_eq!(layout.try_decode("FBFBBFFRBR"), Err(DecodeError::WrongHalf { column: 9, found: 'B' }));
+        assert_eq!(layout.try_decode("FBFLBFFRLR"), Err(DecodeError::WrongHalf { column: 4, found: 'L' }));
+        assert_eq!(layout.try_decode("FBFBBFFRLé"), Err(DecodeError::InvalidChar { column: 10, found: 'é' }));
+        assert_eq!(SeatLayout::new(3, 5).try_decode("BBLLL"), Err(DecodeError::OutsideLayout { row: 3, col: 0 }));
+
+        assert_eq!(DecodeError::WrongHalf { column: 9, found: 'B' }.to_string(),
+                   "column 9: found 'B' where L or R was expected");
+    }
+
+    #[test]
+    fn lenient_decode_does_not_panic() {
+        assert_eq!(decode("FBF"), 32 * 8);
+        assert_eq!(decode("FBéBBFFRLR"), 56 * 8 + 5);
+    }
+
+    #[test]
+    fn try_decode_lines() {
+        let layout = SeatLayout::PUZZLE;
+
+        let seats = layout.try_decode_lines(INPUT).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(seats.len(), INPUT.lines().count());
+
+        let error = layout.try_decode_lines("FBFBBFFRLR\nBFFFBBFRRR\nFBFBBFFRL\nFBXBBFFRRL")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert_eq!(error, PassError { line: 3, error: DecodeError::WrongLength { expected: 10, actual: 9 } });
+        assert_eq!(error.to_string(), "line 3: expected 10 characters, found 9");
+    }
+
+    #[test]
+    fn fast_decode_matches_reference() {
+        let layouts = [SeatLayout::PUZZLE, SeatLayout::new(1, 8), SeatLayout::new(300, 4), SeatLayout::new(64, 1024)];
+
+        for layout in &layouts {
+            for seat_id in 0..layout.seat_count() {
+                let pass = layout.encode(seat_id).unwrap();
+                assert_eq!(fast::decode(pass.as_bytes()), layout.decode(&pass), "{}", pass);
+            }
+        }
+    }
+
+    #[test]
+    fn fast_decode_all_matches_reference() {
+        let expected = INPUT.lines().map(crate::decode).collect::<Vec<_>>();
+        assert_eq!(fast::decode_all(INPUT, 10).collect::<Vec<_>>(), expected);
+
+        let expected = SAMPLE_01.lines().map(crate::decode).collect::<Vec<_>>();
+        assert_eq!(fast::decode_all(SAMPLE_01, 10).collect::<Vec<_>>(), expected);
+
+        // not strided, every line is trimmed and blank lines are skipped
+        let crlf = SAMPLE_01.replace('\n', "\r\n");
+        assert_eq!(fast::decode_all(&crlf, 10).collect::<Vec<_>>(), expected);
+        let trailing_blank = format!("{}\n\n", SAMPLE_01.trim_end());
+        assert_eq!(fast::decode_all(&trailing_blank, 10).collect::<Vec<_>>(), expected);
+        let whitespace = format!(" {}\n\n  \n", SAMPLE_01.trim_end().replace('\n', " \n"));
+        assert_eq!(fast::decode_all(&whitespace, 10).collect::<Vec<_>>(), expected);
+
+        // a truncated last pass has as many bytes as an unterminated whole one
+        let truncated = format!("{}\n", &SAMPLE_01.trim_end()[..SAMPLE_01.trim_end().len() - 1]);
+        let expected_truncated = truncated.lines().map(|line| fast::decode(line.as_bytes())).collect::<Vec<_>>();
+        assert_eq!(fast::decode_all(&truncated, 10).collect::<Vec<_>>(), expected_truncated);
+        // passes split across lines that happen to put a `\n` at every stride
+        assert_eq!(fast::decode_all("FBFBB\nFRLR\nBFFFBBFRRR\n", 10).collect::<Vec<_>>(),
+                   vec![fast::decode(b"FBFBB"), fast::decode(b"FRLR"), 567]);
+
+        assert_eq!(solve_input(&INPUT.replace('\n', "\r\n")), (894, 579));
+        assert_eq!(solve_input(&format!("{}\n\n", INPUT.trim_end())), (894, 579));
+    }
+
+    #[test]
+    fn max_and_missing() {
+        assert_eq!(fast::max_and_missing(vec![3, 5, 6, 4, 8].into_iter()), Some((8, 7)));
+        assert_eq!(fast::max_and_missing(vec![2, 0, 3].into_iter()), Some((3, 1)));
+        assert_eq!(fast::max_and_missing(vec![5, 7].into_iter()), Some((7, 6)));
+
+        // no seats, no gap, two gaps
+        assert_eq!(fast::max_and_missing(vec![].into_iter()), None);
+        assert_eq!(fast::max_and_missing(vec![4].into_iter()), None);
+        assert_eq!(fast::max_and_missing(vec![1, 2, 3].into_iter()), None);
+        assert_eq!(fast::max_and_missing(vec![1, 3, 5].into_iter()), None);
+        // a duplicate, even when the seat count matches a single gap
+        assert_eq!(fast::max_and_missing(vec![1, 1, 3].into_iter()), None);
+        assert_eq!(fast::max_and_missing(vec![0, 0, 3].into_iter()), None);
+    }
+
+    #[test]
+    fn solve_matches_reference() {
+        assert_eq!(solve(), solve_reference());
+        assert_eq!(solve(), (894, 579));
+    }
+
     #[cfg(all(feature = "unstable", test))]
     mod bench {
         extern crate test;
@@ -96,5 +300,26 @@ mod tests {
                 solve()
             });
         }
+
+        #[bench]
+        fn d05_reference(b: &mut Bencher) {
+            b.iter(|| {
+                solve_reference()
+            });
+        }
+
+        #[bench]
+        fn d05_decode_reference(b: &mut Bencher) {
+            b.iter(|| {
+                INPUT.lines().map(crate::decode).max()
+            });
+        }
+
+        #[bench]
+        fn d05_decode_fast(b: &mut Bencher) {
+            b.iter(|| {
+                fast::decode_all(INPUT, 10).max()
+            });
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,172 @@
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+
+use crate::{DecodeError, PassError, SeatLayout};
+
+const RESET: &str = "\x1b[0m";
+const DIM: &str = "\x1b[2m";
+const HIGHLIGHT: &str = "\x1b[1;41m";
+const WARN: &str = "\x1b[1;33m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Ascii,
+    /// Ascii, with empty seats and duplicates colored using ANSI escape codes
+    Ansi,
+}
+
+/// Where an empty seat is relative to the occupied ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vacancy {
+    /// Before the first occupied seat, the front rows missing from this aircraft
+    Front,
+    /// After the last occupied seat, the back rows missing from this aircraft
+    Back,
+    /// Between two occupied seats
+    Interior,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmptySeat {
+    pub seat_id: usize,
+    pub row: usize,
+    pub col: usize,
+    pub vacancy: Vacancy,
+}
+
+/// A seat claimed by more than one boarding pass
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Duplicate {
+    pub seat_id: usize,
+    /// 1-based lines of every pass for this seat
+    pub lines: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occupancy {
+    pub empty: Vec<EmptySeat>,
+    pub duplicates: Vec<Duplicate>,
+    /// 1-based lines of passes that decode to a seat outside the layout
+    pub outside: Vec<usize>,
+    /// Passes that aren't a well formed pass for this layout, which claim no seat
+    pub malformed: Vec<PassError>,
+}
+
+impl Occupancy {
+    /// Empty seats between occupied ones, the candidates for a missing passenger's seat
+    pub fn interior(&self) -> impl Iterator<Item=&EmptySeat> {
+        self.empty.iter().filter(|seat| seat.vacancy == Vacancy::Interior)
+    }
+}
+
+/// Every seat of a [SeatLayout], along with the boarding passes that claim it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeatMap {
+    layout: SeatLayout,
+    passes: Vec<Vec<usize>>,
+    outside: Vec<usize>,
+    malformed: Vec<PassError>,
+}
+
+impl SeatMap {
+    /// Decodes one boarding pass per line of [input], blank lines are skipped
+    pub fn from_passes(layout: SeatLayout, input: &str) -> SeatMap {
+        let mut passes = vec![vec![]; layout.seat_count()];
+        let mut outside = vec![];
+        let mut malformed = vec![];
+
+        for (i, pass) in input.lines().enumerate() {
+            let pass = pass.trim();
+            if pass.is_empty() {
+                continue;
+            }
+
+            match layout.try_decode(pass) {
+                Ok(seat_id) => passes[seat_id].push(i + 1),
+                Err(DecodeError::OutsideLayout { .. }) => outside.push(i + 1),
+                Err(error) => malformed.push(PassError { line: i + 1, error }),
+            }
+        }
+
+        SeatMap { layout, passes, outside, malformed }
+    }
+
+    pub fn layout(&self) -> SeatLayout {
+        self.layout
+    }
+
+    /// Number of boarding passes claiming [seat_id]
+    pub fn passes(&self, seat_id: usize) -> usize {
+        self.passes[seat_id].len()
+    }
+
+    pub fn is_occupied(&self, seat_id: usize) -> bool {
+        self.passes(seat_id) > 0
+    }
+
+    pub fn occupancy(&self) -> Occupancy {
+        let occupied = self.occupied_range();
+        let empty = (0..self.layout.seat_count())
+            .filter(|&seat_id| !self.is_occupied(seat_id))
+            .map(|seat_id| {
+                let (row, col) = self.layout.seat(seat_id);
+                EmptySeat { seat_id, row, col, vacancy: vacancy(seat_id, &occupied) }
+            })
+            .collect();
+
+        let duplicates = self.passes.iter().enumerate()
+            .filter(|(_, lines)| lines.len() > 1)
+            .map(|(seat_id, lines)| Duplicate { seat_id, lines: lines.clone() })
+            .collect();
+
+        Occupancy { empty, duplicates, outside: self.outside.clone(), malformed: self.malformed.clone() }
+    }
+
+    /// First to last occupied seat id, `None` if every seat is empty
+    fn occupied_range(&self) -> Option<RangeInclusive<usize>> {
+        let first = (0..self.layout.seat_count()).find(|&seat_id| self.is_occupied(seat_id))?;
+        let last = (0..self.layout.seat_count()).rev().find(|&seat_id| self.is_occupied(seat_id))?;
+        Some(first..=last)
+    }
+
+    /// One line per row, prefixed with the row number.
+    ///
+    /// `#` is an occupied seat, `.` is missing at the front or back, `O` is an interior gap,
+    /// and `2`-`9` (or `*` for more) are seats claimed by that many passes.
+    pub fn render(&self, style: Style) -> String {
+        let occupied = self.occupied_range();
+        let width = (self.layout.rows() - 1).to_string().len();
+
+        let mut out = String::new();
+        for row in 0..self.layout.rows() {
+            write!(out, "{:>width$} ", row, width = width).unwrap();
+
+            for col in 0..self.layout.cols() {
+                let seat_id = self.layout.seat_id(row, col);
+                let (c, color) = match self.passes(seat_id) {
+                    0 if vacancy(seat_id, &occupied) == Vacancy::Interior => ('O', HIGHLIGHT),
+                    0 => ('.', DIM),
+                    1 => ('#', ""),
+                    n @ 2..=9 => (std::char::from_digit(n as u32, 10).unwrap(), WARN),
+                    _ => ('*', WARN),
+                };
+
+                match style {
+                    Style::Ansi if !color.is_empty() => write!(out, "{}{}{}", color, c, RESET).unwrap(),
+                    _ => out.push(c),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Classifies the empty seat [seat_id], every seat of an empty aircraft is at the front
+fn vacancy(seat_id: usize, occupied: &Option<RangeInclusive<usize>>) -> Vacancy {
+    match occupied {
+        Some(occupied) if seat_id > *occupied.end() => Vacancy::Back,
+        Some(occupied) if seat_id > *occupied.start() => Vacancy::Interior,
+        _ => Vacancy::Front,
+    }
+}
@@ -0,0 +1,153 @@
+/// A set of questions answered yes, each question identified by its index in an [Alphabet]
+pub trait AnswerSet: Clone {
+    /// Size of the largest alphabet this set can hold
+    const CAPACITY: usize;
+
+    /// No question answered, for an alphabet of [size] questions
+    fn none(size: usize) -> Self;
+
+    /// Every question answered, for an alphabet of [size] questions
+    fn all(size: usize) -> Self;
+
+    fn insert(&mut self, question: usize);
+
+    fn contains(&self, question: usize) -> bool;
+
+    fn union(&self, other: &Self) -> Self;
+
+    fn intersection(&self, other: &Self) -> Self;
+
+    fn count(&self) -> usize;
+}
+
+macro_rules! impl_answer_set_for_int {
+    ($($t:ty),*) => {$(
+        impl AnswerSet for $t {
+            const CAPACITY: usize = <$t>::BITS as usize;
+
+            fn none(_: usize) -> Self {
+                0
+            }
+
+            fn all(size: usize) -> Self {
+                if size >= Self::CAPACITY { <$t>::MAX } else { (1 << size) - 1 }
+            }
+
+            fn insert(&mut self, question: usize) {
+                *self |= 1 << question;
+            }
+
+            fn contains(&self, question: usize) -> bool {
+                self >> question & 1 == 1
+            }
+
+            fn union(&self, other: &Self) -> Self {
+                self | other
+            }
+
+            fn intersection(&self, other: &Self) -> Self {
+                self & other
+            }
+
+            fn count(&self) -> usize {
+                self.count_ones() as usize
+            }
+        }
+    )*};
+}
+
+impl_answer_set_for_int!(u32, u64, u128);
+
+/// Dynamically sized [AnswerSet], for alphabets of any size
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitSet(Vec<u64>);
+
+impl AnswerSet for BitSet {
+    const CAPACITY: usize = usize::MAX;
+
+    fn none(size: usize) -> Self {
+        BitSet(vec![0; size.div_ceil(64)])
+    }
+
+    fn all(size: usize) -> Self {
+        let mut words = vec![u64::MAX; size / 64];
+        if !size.is_multiple_of(64) {
+            words.push((1 << (size % 64)) - 1);
+        }
+        BitSet(words)
+    }
+
+    fn insert(&mut self, question: usize) {
+        self.0[question / 64] |= 1 << (question % 64);
+    }
+
+    fn contains(&self, question: usize) -> bool {
+        self.0.get(question / 64).is_some_and(|word| word >> (question % 64) & 1 == 1)
+    }
+
+    fn union(&self, other: &Self) -> Self {
+        BitSet(self.0.iter().zip(&other.0).map(|(a, b)| a | b).collect())
+    }
+
+    fn intersection(&self, other: &Self) -> Self {
+        BitSet(self.0.iter().zip(&other.0).map(|(a, b)| a & b).collect())
+    }
+
+    fn count(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+}
+
+/// The characters used to label questions, a question's index is its position in the alphabet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    chars: Vec<char>,
+    /// index + 1 of each ascii char, 0 if it isn't part of the alphabet
+    ascii: [u32; 128],
+}
+
+impl Alphabet {
+    /// `a` to `z`, the questions from the puzzle
+    pub fn lowercase() -> Alphabet {
+        Alphabet::new(('a'..='z').collect::<String>().as_str())
+    }
+
+    /// Panics if [chars] contains a duplicate
+    pub fn new(chars: &str) -> Alphabet {
+        let mut alphabet = Alphabet { chars: vec![], ascii: [0; 128] };
+
+        for c in chars.chars() {
+            assert!(alphabet.index_of(c).is_none(), "duplicate question {:?} in alphabet", c);
+            if c.is_ascii() {
+                alphabet.ascii[c as usize] = alphabet.chars.len() as u32 + 1;
+            }
+            alphabet.chars.push(c);
+        }
+
+        alphabet
+    }
+
+    pub fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    pub fn index_of(&self, c: char) -> Option<usize> {
+        if c.is_ascii() {
+            match self.ascii[c as usize] {
+                0 => None,
+                i => Some(i as usize - 1),
+            }
+        } else {
+            self.chars.iter().position(|&it| it == c)
+        }
+    }
+
+    /// The label of [question]
+    pub fn char(&self, question: usize) -> char {
+        self.chars[question]
+    }
+}
@@ -1,21 +1,150 @@
 #![cfg_attr(feature = "unstable", feature(test))]
 
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+
+use records::{Record, Records};
+
+pub use crate::answers::{Alphabet, AnswerSet, BitSet};
+pub use crate::stats::{quorum, Histogram, SurveyStats};
+
+mod answers;
+mod stats;
+
 const INPUT: &'static str = include_str!("../input");
 
-/// returns Iterator over each group, such that a group is an Iterator over each person
-/// a person is a bitmask of each question answered yes
-fn parse(input: &str) -> impl Iterator<Item=impl Iterator<Item=usize> + '_> + '_ {
-    input.split("\n\n").map(|group| group.lines()
-        .map(|person| person.chars()
-            .filter(char::is_ascii_lowercase)
-            .map(|c| c as usize - 'a' as usize)
-            .fold(0usize, |acc, c| acc | (1 << c))))
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The alphabet has more questions than the [AnswerSet] can hold
+    AlphabetTooLarge { size: usize, capacity: usize },
+    /// An answer that isn't part of the alphabet, [line] and [column] are 1-based
+    UnknownAnswer { line: usize, column: usize, found: char },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::AlphabetTooLarge { size, capacity } => {
+                write!(f, "alphabet of {} questions doesn't fit in an answer set of {}", size, capacity)
+            }
+            ParseError::UnknownAnswer { line, column, found } => {
+                write!(f, "line {}, column {}: {:?} is not part of the alphabet", line, column, found)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Error from a solver reading its input from a [BufRead]
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "failed to read input: {}", e),
+            ReadError::Parse(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for ReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadError::Io(e) => Some(e),
+            ReadError::Parse(e) => Some(e),
+        }
+    }
+}
+
+/// returns Iterator over each group read from [reader], such that a group is a Vec of each person
+/// a person is the set of each question answered yes
+pub fn parse_reader<'a, S: AnswerSet + 'a, R: BufRead + 'a>(
+    reader: R,
+    alphabet: &'a Alphabet,
+) -> Result<impl Iterator<Item=Result<Vec<S>, ReadError>> + 'a, ParseError> {
+    if alphabet.len() > S::CAPACITY {
+        return Err(ParseError::AlphabetTooLarge { size: alphabet.len(), capacity: S::CAPACITY });
+    }
+
+    Ok(Records::new(reader).map(move |group| {
+        let group = group.map_err(ReadError::Io)?;
+        parse_group(&group, alphabet).map_err(ReadError::Parse)
+    }))
+}
+
+/// [parse_reader] for input that's already in memory
+fn parse<'a, S: AnswerSet + 'a>(
+    input: &'a str,
+    alphabet: &'a Alphabet,
+) -> Result<impl Iterator<Item=Result<Vec<S>, ParseError>> + 'a, ParseError> {
+    Ok(parse_reader(input.as_bytes(), alphabet)?.map(|group| {
+        group.map_err(|e| match e {
+            ReadError::Parse(e) => e,
+            ReadError::Io(e) => unreachable!("reading valid UTF-8 from memory can't fail: {}", e),
+        })
+    }))
+}
+
+fn parse_group<S: AnswerSet>(group: &Record, alphabet: &Alphabet) -> Result<Vec<S>, ParseError> {
+    group.lines().enumerate()
+        .map(|(i, person)| parse_person(person, alphabet, group.first_line() + i))
+        .collect()
+}
+
+fn parse_person<S: AnswerSet>(person: &str, alphabet: &Alphabet, line: usize) -> Result<S, ParseError> {
+    person.chars().enumerate()
+        .filter(|(_, c)| !c.is_whitespace())
+        .try_fold(S::none(alphabet.len()), |mut answers, (i, c)| {
+            let question = alphabet.index_of(c)
+                .ok_or(ParseError::UnknownAnswer { line, column: i + 1, found: c })?;
+            answers.insert(question);
+            Ok(answers)
+        })
+}
+
+/// Sum over each group of the questions anyone answered, and of the questions everyone answered
+fn count_groups<S: AnswerSet, E>(
+    groups: impl Iterator<Item=Result<Vec<S>, E>>,
+    size: usize,
+) -> Result<(usize, usize), E> {
+    groups
+        .map(|group| group.map(|group| group.iter()
+            .fold((S::none(size), S::all(size)), |(a1, a2), person| {
+                (a1.union(person), a2.intersection(person))
+            })))
+        .try_fold((0, 0), |(a1, a2), group| {
+            group.map(|(m1, m2)| (a1 + m1.count(), a2 + m2.count()))
+        })
+}
+
+fn solve_with<S: AnswerSet>(input: &str, alphabet: &Alphabet) -> Result<(usize, usize), ParseError> {
+    count_groups(parse::<S>(input, alphabet)?, alphabet.len())
+}
+
+/// Solves both parts while streaming groups from [reader], holding a single group in memory at a time
+pub fn solve_reader<S: AnswerSet, R: BufRead>(reader: R, alphabet: &Alphabet) -> Result<(usize, usize), ReadError> {
+    count_groups(parse_reader::<S, R>(reader, alphabet).map_err(ReadError::Parse)?, alphabet.len())
+}
+
+/// Per group and overall histograms of the questions answered in [input]
+pub fn survey_stats<S: AnswerSet>(input: &str, alphabet: &Alphabet) -> Result<SurveyStats, ParseError> {
+    let groups = parse::<S>(input, alphabet)?.collect::<Result<Vec<_>, _>>()?;
+    Ok(SurveyStats::new(&groups, alphabet.len()))
 }
 
 pub fn solve() -> (usize, usize) {
-    parse(INPUT)
-        .map(|group| group.fold((0, usize::MAX), |(a1, a2), mask| (a1 | mask, a2 & mask)))
-        .fold((0, 0), |(a1, a2), (m1, p2)| (a1 + m1.count_ones() as usize, a2 + p2.count_ones() as usize))
+    solve_input(INPUT)
+}
+
+/// Panics if [input] answers a question outside `a` to `z`
+pub fn solve_input(input: &str) -> (usize, usize) {
+    solve_with::<u32>(input, &Alphabet::lowercase()).unwrap()
 }
 
 // ============================================================================================== //
@@ -28,8 +157,8 @@ mod tests {
     const SAMPLE_02: &'static str = include_str!("../sample02");
 
     fn solve_part_1(input: &str) -> usize {
-        parse(input)
-            .map(|group| group
+        parse::<u32>(input, &Alphabet::lowercase()).unwrap()
+            .map(|group| group.unwrap().iter()
                 .fold(0, |acc, person| acc | person)
                 .count_ones() as usize
             )
@@ -37,9 +166,9 @@ mod tests {
     }
 
     fn solve_part_2(input: &str) -> usize {
-        parse(input)
-            .map(|group| group
-                .fold(usize::MAX, |acc, person| acc & person)
+        parse::<u32>(input, &Alphabet::lowercase()).unwrap()
+            .map(|group| group.unwrap().iter()
+                .fold(u32::MAX, |acc, person| acc & person)
                 .count_ones() as usize
             )
             .sum()
@@ -81,6 +210,115 @@ mod tests {
         assert_eq!(actual, (6778, 3406));
     }
 
+    #[test]
+    fn answer_set_types_agree() {
+        let alphabet = &Alphabet::lowercase();
+
+        assert_eq!(solve_with::<u32>(INPUT, alphabet), Ok((6778, 3406)));
+        assert_eq!(solve_with::<u128>(INPUT, alphabet), Ok((6778, 3406)));
+        assert_eq!(solve_with::<BitSet>(INPUT, alphabet), Ok((6778, 3406)));
+    }
+
+    #[test]
+    fn wide_alphabet() {
+        let labels = ('a'..='z').chain('A'..='Z').chain('0'..='9').chain("αβγδεζηθ".chars()).collect::<String>();
+        let alphabet = &Alphabet::new(&labels);
+        let input = "aZ9θ\nZθ\n\nα\nβ";
+
+        assert_eq!(alphabet.len(), 70);
+        assert_eq!(solve_with::<u128>(input, alphabet), Ok((6, 2)));
+        assert_eq!(solve_with::<BitSet>(input, alphabet), Ok((6, 2)));
+        assert_eq!(solve_with::<u32>(input, alphabet), Err(ParseError::AlphabetTooLarge { size: 70, capacity: 32 }));
+    }
+
+    #[test]
+    fn unknown_answers() {
+        let alphabet = &Alphabet::new("abc");
+
+        assert_eq!(solve_with::<u32>("ab\r\nc\r\n\nb", alphabet), Ok((4, 1)));
+        let error = solve_with::<u32>("ab\nc\n\nb\nbad", alphabet).unwrap_err();
+        assert_eq!(error, ParseError::UnknownAnswer { line: 5, column: 3, found: 'd' });
+        assert_eq!(error.to_string(), "line 5, column 3: 'd' is not part of the alphabet");
+    }
+
+    #[test]
+    fn bit_set() {
+        let mut set = BitSet::none(130);
+        set.insert(0);
+        set.insert(64);
+        set.insert(129);
+
+        assert!(set.contains(129) && !set.contains(128));
+        assert_eq!(set.count(), 3);
+        assert_eq!(BitSet::all(130).count(), 130);
+        assert_eq!(BitSet::all(130).intersection(&set), set);
+        assert_eq!(u32::all(26).count(), 26);
+        assert_eq!(u32::all(32), u32::MAX);
+    }
+
+    #[test]
+    fn quorum_of_group() {
+        let alphabet = &Alphabet::lowercase();
+        let group = parse::<u32>("abcx\nabcy\nabcz\nxyz\na", alphabet).unwrap().next().unwrap().unwrap();
+        let labels = |answers: u32| (0..26)
+            .filter(|&q| answers.contains(q))
+            .map(|q| alphabet.char(q))
+            .collect::<String>();
+
+        assert_eq!(labels(quorum(&group, 1, 26)), "abcxyz");
+        assert_eq!(labels(quorum(&group, 2, 26)), "abcxyz");
+        assert_eq!(labels(quorum(&group, 3, 26)), "abc");
+        assert_eq!(labels(quorum(&group, 4, 26)), "a");
+        assert_eq!(labels(quorum(&group, 5, 26)), "");
+    }
+
+    #[test]
+    fn quorum_matches_any_and_every() {
+        let alphabet = &Alphabet::lowercase();
+        let stats = survey_stats::<u32>(INPUT, alphabet).unwrap();
+
+        let any = stats.groups.iter().map(|group| group.quorum(1)).sum::<usize>();
+        let every = stats.groups.iter().map(|group| group.quorum(group.people())).sum::<usize>();
+        assert_eq!((any, every), (6778, 3406));
+    }
+
+    #[test]
+    fn survey_histograms() {
+        let alphabet = &Alphabet::new("abcd");
+        let stats = survey_stats::<BitSet>(SAMPLE_02, &Alphabet::new("abc")).unwrap();
+
+        assert_eq!(stats.overall.people(), 11);
+        assert_eq!(stats.overall.counts(), &[8, 4, 3]);
+        assert_eq!(stats.groups[1].counts(), &[1, 1, 1]);
+
+        let stats = survey_stats::<u32>("ab\nb\n\nb", alphabet).unwrap();
+        assert_eq!(stats.groups[0].never_answered().collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(stats.overall.never_answered().collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(stats.to_csv(alphabet), "group,people,a,b,c,d\n0,2,1,2,0,0\n1,1,0,1,0,0\nall,3,1,3,0,0\n");
+    }
+
+    #[test]
+    fn solve_reader_streams_repeated_input() {
+        use std::io::Read;
+
+        let copies = 50;
+        let reader = (0..copies).fold(Box::new(io::empty()) as Box<dyn Read>, |reader, _| {
+            Box::new(Read::chain(reader, INPUT.as_bytes()).chain(&b"\r\n\r\n"[..]))
+        });
+
+        let actual = solve_reader::<u32, _>(io::BufReader::new(reader), &Alphabet::lowercase()).unwrap();
+        assert_eq!(actual, (6778 * copies, 3406 * copies));
+    }
+
+    #[test]
+    fn solve_reader_errors() {
+        let alphabet = &Alphabet::new("abc");
+
+        let error = solve_reader::<u32, _>(&b"ab\n\nabd"[..], alphabet).unwrap_err();
+        assert_eq!(error.to_string(), "line 3, column 3: 'd' is not part of the alphabet");
+        assert!(matches!(solve_reader::<u32, _>(&b"ab\n\n\xff"[..], alphabet), Err(ReadError::Io(_))));
+    }
+
     #[cfg(all(feature = "unstable", test))]
     mod bench {
         extern crate test;
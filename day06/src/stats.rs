@@ -0,0 +1,108 @@
+use std::fmt::Write;
+
+use crate::{Alphabet, AnswerSet};
+
+/// The questions answered yes by at least [k] people of [group], for an alphabet of [size] questions.
+///
+/// A quorum of 1 is anyone, and a quorum of the group size is everyone.
+pub fn quorum<S: AnswerSet>(group: &[S], k: usize, size: usize) -> S {
+    let histogram = Histogram::of_group(group, size);
+
+    (0..size)
+        .filter(|&question| histogram.counts[question] >= k)
+        .fold(S::none(size), |mut answers, question| {
+            answers.insert(question);
+            answers
+        })
+}
+
+/// How many people answered each question yes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    people: usize,
+    counts: Vec<usize>,
+}
+
+impl Histogram {
+    pub fn of_group<S: AnswerSet>(group: &[S], size: usize) -> Histogram {
+        let counts = (0..size)
+            .map(|question| group.iter().filter(|person| person.contains(question)).count())
+            .collect();
+        Histogram { people: group.len(), counts }
+    }
+
+    pub fn people(&self) -> usize {
+        self.people
+    }
+
+    /// Number of people who answered each question, indexed by question
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    /// Number of questions answered by at least [k] people
+    pub fn quorum(&self, k: usize) -> usize {
+        self.counts.iter().filter(|&&count| count >= k).count()
+    }
+
+    pub fn never_answered(&self) -> impl Iterator<Item=usize> + '_ {
+        self.counts.iter().enumerate()
+            .filter(|(_, &count)| count == 0)
+            .map(|(question, _)| question)
+    }
+
+    fn add(&mut self, other: &Histogram) {
+        self.people += other.people;
+        for (count, other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+    }
+}
+
+/// Per group and overall [Histogram]s of a survey
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SurveyStats {
+    pub groups: Vec<Histogram>,
+    pub overall: Histogram,
+}
+
+impl SurveyStats {
+    pub fn new<S: AnswerSet>(groups: &[Vec<S>], size: usize) -> SurveyStats {
+        let groups = groups.iter()
+            .map(|group| Histogram::of_group(group, size))
+            .collect::<Vec<_>>();
+
+        let mut overall = Histogram { people: 0, counts: vec![0; size] };
+        for group in &groups {
+            overall.add(group);
+        }
+
+        SurveyStats { groups, overall }
+    }
+
+    /// One row per group, followed by an `all` row. Columns are the group's 0-based index, its
+    /// number of people, then the number of people answering each question of [alphabet].
+    pub fn to_csv(&self, alphabet: &Alphabet) -> String {
+        let mut out = String::from("group,people");
+        for question in 0..alphabet.len() {
+            match alphabet.char(question) {
+                c @ (',' | '"') => write!(out, ",\"{}\"", c.to_string().replace('"', "\"\"")).unwrap(),
+                c => write!(out, ",{}", c).unwrap(),
+            }
+        }
+        out.push('\n');
+
+        let rows = self.groups.iter().enumerate()
+            .map(|(i, group)| (i.to_string(), group))
+            .chain(std::iter::once(("all".to_owned(), &self.overall)));
+        for (label, histogram) in rows {
+            write!(out, "{},{}", label, histogram.people).unwrap();
+            for count in &histogram.counts {
+                write!(out, ",{}", count).unwrap();
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+}
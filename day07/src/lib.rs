@@ -3,6 +3,10 @@
 const INPUT: &'static str = include_str!("../input");
 
 pub fn solve() -> (&'static str, &'static str) {
+    solve_input(INPUT)
+}
+
+pub fn solve_input(_input: &str) -> (&'static str, &'static str) {
     ("", "")
 }
 
@@ -0,0 +1,150 @@
+#![cfg_attr(feature = "unstable", feature(test))]
+
+pub use crate::vm::{parse, repair, Exit, Instruction, Machine, Op, ParseError, ProgramError, Repair, Step};
+
+mod vm;
+
+const INPUT: &str = include_str!("../input");
+
+/// The accumulator right before any instruction runs a second time
+fn solve_part_1(program: &[Instruction]) -> i64 {
+    match Machine::new(program).run() {
+        Exit::Loop { acc, .. } => acc,
+        exit => panic!("expected the program to loop, it stopped with {:?}", exit),
+    }
+}
+
+/// The accumulator once the program is repaired and terminates
+fn solve_part_2(program: &[Instruction]) -> i64 {
+    repair(program).expect("no single flipped instruction makes the program terminate").acc
+}
+
+pub fn solve_input(input: &str) -> (i64, i64) {
+    let program = &parse(input).unwrap();
+    (solve_part_1(program), solve_part_2(program))
+}
+
+pub fn solve() -> (i64, i64) {
+    solve_input(INPUT)
+}
+
+// ============================================================================================== //
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const SAMPLE_01: &str = include_str!("../sample01");
+
+    #[test]
+    fn part_1_sample_input() {
+        assert_eq!(solve_part_1(&parse(SAMPLE_01).unwrap()), 5);
+    }
+
+    #[test]
+    fn part_1_puzzle_input() {
+        assert_eq!(solve_part_1(&parse(INPUT).unwrap()), 241);
+    }
+
+    #[test]
+    fn part_2_sample_input() {
+        assert_eq!(solve_part_2(&parse(SAMPLE_01).unwrap()), 8);
+    }
+
+    #[test]
+    fn part_2_puzzle_input() {
+        assert_eq!(solve_part_2(&parse(INPUT).unwrap()), 248);
+    }
+
+    #[test]
+    fn parse_instructions() {
+        assert_eq!("jmp -3".parse(), Ok(Instruction { op: Op::Jmp, arg: -3 }));
+        assert_eq!("acc +0".parse::<Instruction>().unwrap().to_string(), "acc +0");
+
+        let error = parse("nop +0\n\nmul +2").unwrap_err();
+        assert_eq!(error.to_string(), "line 3: unknown operation \"mul\"");
+        assert_eq!(parse("acc").unwrap_err().error, ParseError::MissingArgument);
+        assert_eq!(parse("acc one").unwrap_err().error, ParseError::InvalidArgument("one".to_owned()));
+    }
+
+    #[test]
+    fn step_by_step() {
+        let program = parse(SAMPLE_01).unwrap();
+        let mut machine = Machine::new(&program);
+
+        let steps = (0..4).map(|_| machine.step().unwrap()).collect::<Vec<_>>();
+        assert_eq!(steps.iter().map(|step| step.pc).collect::<Vec<_>>(), vec![0, 1, 2, 6]);
+        assert_eq!(steps[3], Step { pc: 6, instruction: Instruction { op: Op::Acc, arg: 1 }, acc: 2, next: 7 });
+        assert_eq!(steps[2].to_string(), "    2  jmp +4    acc = 1      next = 6");
+        assert_eq!((machine.pc(), machine.acc()), (7, 2));
+    }
+
+    #[test]
+    fn trace_until_loop() {
+        let program = parse(SAMPLE_01).unwrap();
+        let mut trace = vec![];
+        let exit = Machine::new(&program).run_traced(|step| trace.push(step.pc));
+
+        assert_eq!(exit, Exit::Loop { acc: 5, pc: 1 });
+        assert_eq!(trace, vec![0, 1, 2, 6, 7, 3, 4]);
+    }
+
+    #[test]
+    fn exits() {
+        let program = parse("nop +0\njmp +2\nacc +1").unwrap();
+        assert_eq!(Machine::new(&program).run(), Exit::Terminated { acc: 0 });
+
+        let program = parse("acc +3\njmp -2").unwrap();
+        assert_eq!(Machine::new(&program).run(), Exit::OutOfBounds { acc: 3, pc: -1 });
+        assert_eq!(Machine::new(&[]).run(), Exit::Terminated { acc: 0 });
+
+        let program = parse(&format!("acc +{}\nacc +1\nacc +1", i64::MAX)).unwrap();
+        let mut machine = Machine::new(&program);
+        assert_eq!(machine.run(), Exit::Overflow { acc: i64::MAX, pc: 1 });
+        assert_eq!((machine.pc(), machine.step()), (1, None));
+
+        let program = parse(&format!("nop +0\njmp +{}", i64::MAX)).unwrap();
+        assert_eq!(Machine::new(&program).run(), Exit::Overflow { acc: 0, pc: 1 });
+        let program = parse(&format!("jmp {}", i64::MIN)).unwrap();
+        assert_eq!(Machine::new(&program).run(), Exit::OutOfBounds { acc: 0, pc: i64::MIN as isize });
+    }
+
+    #[test]
+    fn repair_flips_one_instruction() {
+        let program = parse(SAMPLE_01).unwrap();
+        assert_eq!(repair(&program), Some(Repair { pc: 7, instruction: Instruction { op: Op::Nop, arg: -4 }, acc: 8 }));
+        assert_eq!(repair(&parse("acc +1\njmp +0\nacc +1").unwrap()).map(|it| it.acc), Some(2));
+        assert_eq!(repair(&parse("jmp +0\njmp -1").unwrap()), None);
+    }
+
+    #[cfg(all(feature = "unstable", test))]
+    mod bench {
+        extern crate test;
+
+        use crate::*;
+        use test::Bencher;
+
+        #[bench]
+        fn d08_parse(b: &mut Bencher) {
+            b.iter(|| {
+                parse(INPUT).unwrap();
+            });
+        }
+
+        #[bench]
+        fn d08p1(b: &mut Bencher) {
+            let program = &parse(INPUT).unwrap();
+            b.iter(|| {
+                solve_part_1(program);
+            });
+        }
+
+        #[bench]
+        fn d08p2(b: &mut Bencher) {
+            let program = &parse(INPUT).unwrap();
+            b.iter(|| {
+                solve_part_2(program);
+            });
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    /// Adds the argument to the accumulator
+    Acc,
+    /// Jumps relative to itself by the argument
+    Jmp,
+    /// Does nothing
+    Nop,
+}
+
+impl Op {
+    fn name(self) -> &'static str {
+        match self {
+            Op::Acc => "acc",
+            Op::Jmp => "jmp",
+            Op::Nop => "nop",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    pub op: Op,
+    pub arg: i64,
+}
+
+impl Instruction {
+    /// `jmp` becomes `nop` and vice versa, `None` for `acc`
+    pub fn flipped(self) -> Option<Instruction> {
+        let op = match self.op {
+            Op::Acc => return None,
+            Op::Jmp => Op::Nop,
+            Op::Nop => Op::Jmp,
+        };
+        Some(Instruction { op, ..self })
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:+}", self.op.name(), self.arg)
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (op, arg) = s.trim().split_once(' ').ok_or(ParseError::MissingArgument)?;
+        let op = match op {
+            "acc" => Op::Acc,
+            "jmp" => Op::Jmp,
+            "nop" => Op::Nop,
+            _ => return Err(ParseError::UnknownOp(op.to_owned())),
+        };
+        let arg = arg.parse().map_err(|_| ParseError::InvalidArgument(arg.to_owned()))?;
+        Ok(Instruction { op, arg })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    MissingArgument,
+    UnknownOp(String),
+    InvalidArgument(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MissingArgument => f.write_str("expected an operation and an argument"),
+            ParseError::UnknownOp(op) => write!(f, "unknown operation {:?}", op),
+            ParseError::InvalidArgument(arg) => write!(f, "invalid argument {:?}", arg),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// A [ParseError] in a program listing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramError {
+    /// 1-based line of the instruction
+    pub line: usize,
+    pub error: ParseError,
+}
+
+impl fmt::Display for ProgramError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl Error for ProgramError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Parses one instruction per line, blank lines are skipped
+pub fn parse(input: &str) -> Result<Vec<Instruction>, ProgramError> {
+    input.lines().enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| line.parse().map_err(|error| ProgramError { line: i + 1, error }))
+        .collect()
+}
+
+/// One executed instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    /// Index of the executed instruction
+    pub pc: usize,
+    pub instruction: Instruction,
+    /// The accumulator after executing the instruction
+    pub acc: i64,
+    /// Index of the next instruction, which may be outside the program
+    pub next: isize,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>5}  {:<9} acc = {:<6} next = {}", self.pc, self.instruction.to_string(), self.acc, self.next)
+    }
+}
+
+/// Why a program stopped running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    /// Ran the instruction right after the last one
+    Terminated { acc: i64 },
+    /// About to run [pc] a second time, which would repeat forever
+    Loop { acc: i64, pc: usize },
+    /// Jumped somewhere other than an instruction or the end of the program
+    OutOfBounds { acc: i64, pc: isize },
+    /// Running [pc] would overflow the accumulator or the next instruction's index
+    Overflow { acc: i64, pc: usize },
+}
+
+impl Exit {
+    pub fn acc(&self) -> i64 {
+        match *self {
+            Exit::Terminated { acc }
+            | Exit::Loop { acc, .. }
+            | Exit::OutOfBounds { acc, .. }
+            | Exit::Overflow { acc, .. } => acc,
+        }
+    }
+}
+
+/// Executes a program one instruction at a time
+#[derive(Debug, Clone)]
+pub struct Machine<'a> {
+    program: &'a [Instruction],
+    pc: isize,
+    acc: i64,
+}
+
+impl<'a> Machine<'a> {
+    pub fn new(program: &'a [Instruction]) -> Machine<'a> {
+        Machine { program, pc: 0, acc: 0 }
+    }
+
+    pub fn pc(&self) -> isize {
+        self.pc
+    }
+
+    pub fn acc(&self) -> i64 {
+        self.acc
+    }
+
+    /// The instruction at pc, `None` once the program has stopped
+    fn current(&self) -> Option<(usize, Instruction)> {
+        usize::try_from(self.pc).ok()
+            .and_then(|pc| self.program.get(pc).map(|&instruction| (pc, instruction)))
+    }
+
+    /// Executes a single instruction, `None` if pc isn't an instruction of the program or running
+    /// it would overflow, in which case the machine is left as it was
+    pub fn step(&mut self) -> Option<Step> {
+        let (pc, instruction) = self.current()?;
+
+        let (acc, next) = match instruction.op {
+            Op::Acc => (self.acc.checked_add(instruction.arg)?, self.pc.checked_add(1)?),
+            Op::Jmp => (self.acc, isize::try_from(instruction.arg).ok().and_then(|arg| self.pc.checked_add(arg))?),
+            Op::Nop => (self.acc, self.pc.checked_add(1)?),
+        };
+        self.acc = acc;
+        self.pc = next;
+
+        Some(Step { pc, instruction, acc, next })
+    }
+
+    /// Runs until the program ends, leaves the program, or an instruction would run a second time
+    pub fn run(&mut self) -> Exit {
+        self.run_traced(|_| {})
+    }
+
+    /// Like [run], passing every executed instruction to [on_step]
+    pub fn run_traced(&mut self, mut on_step: impl FnMut(&Step)) -> Exit {
+        let mut visited = vec![false; self.program.len()];
+
+        loop {
+            let pc = match self.current() {
+                Some((pc, _)) if visited[pc] => return Exit::Loop { acc: self.acc, pc },
+                Some((pc, _)) => pc,
+                None if self.pc == self.program.len() as isize => return Exit::Terminated { acc: self.acc },
+                None => return Exit::OutOfBounds { acc: self.acc, pc: self.pc },
+            };
+            visited[pc] = true;
+
+            match self.step() {
+                Some(step) => on_step(&step),
+                None => return Exit::Overflow { acc: self.acc, pc },
+            }
+        }
+    }
+}
+
+/// A single flipped instruction that makes a program terminate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repair {
+    /// Index of the flipped instruction
+    pub pc: usize,
+    /// The instruction after flipping
+    pub instruction: Instruction,
+    /// The accumulator when the repaired program terminates
+    pub acc: i64,
+}
+
+/// Finds the first `jmp` or `nop` of [program] that, once flipped, makes it terminate
+pub fn repair(program: &[Instruction]) -> Option<Repair> {
+    let mut patched = program.to_vec();
+
+    (0..program.len()).find_map(|pc| {
+        let instruction = program[pc].flipped()?;
+
+        patched[pc] = instruction;
+        let exit = Machine::new(&patched).run();
+        patched[pc] = program[pc];
+
+        match exit {
+            Exit::Terminated { acc } => Some(Repair { pc, instruction, acc }),
+            _ => None,
+        }
+    })
+}
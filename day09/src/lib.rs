@@ -0,0 +1,127 @@
+#![cfg_attr(feature = "unstable", feature(test))]
+
+pub use day01::{BruteForce, SearchAlg, TwoPointer};
+
+pub use crate::xmas::{contiguous_range, first_invalid, Check, Validator};
+
+mod xmas;
+
+const INPUT: &str = include_str!("../input");
+
+/// The puzzle checks each number against the 25 before it
+const WINDOW: usize = 25;
+
+/// One number per line, panics on anything else
+pub fn numbers(input: &str) -> impl Iterator<Item=usize> + '_ {
+    input.lines().map(|line| line.trim().parse().expect("failed to parse line"))
+}
+
+fn solve_part_1(input: &str, window: usize) -> usize {
+    first_invalid(numbers(input), window, TwoPointer).expect("every number is valid")
+}
+
+/// Sum of the smallest and largest number of the range summing to the first invalid number
+fn solve_part_2(input: &str, window: usize) -> usize {
+    let numbers = numbers(input).collect::<Vec<_>>();
+    let invalid = first_invalid(numbers.iter().copied(), window, TwoPointer).expect("every number is valid");
+    let range = &numbers[contiguous_range(&numbers, invalid).expect("no range sums to the invalid number")];
+    range.iter().min().unwrap() + range.iter().max().unwrap()
+}
+
+pub fn solve_input(input: &str) -> (usize, usize) {
+    (solve_part_1(input, WINDOW), solve_part_2(input, WINDOW))
+}
+
+pub fn solve() -> (usize, usize) {
+    solve_input(INPUT)
+}
+
+// ============================================================================================== //
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const SAMPLE_01: &str = include_str!("../sample01");
+
+    #[test]
+    fn part_1_sample_input() {
+        assert_eq!(solve_part_1(SAMPLE_01, 5), 127);
+    }
+
+    #[test]
+    fn part_1_puzzle_input() {
+        assert_eq!(solve_part_1(INPUT, WINDOW), 10120913402);
+    }
+
+    #[test]
+    fn part_2_sample_input() {
+        assert_eq!(solve_part_2(SAMPLE_01, 5), 62);
+    }
+
+    #[test]
+    fn part_2_puzzle_input() {
+        assert_eq!(solve_part_2(INPUT, WINDOW), 1175869243);
+    }
+
+    #[test]
+    fn search_algs_agree() {
+        let numbers = numbers(INPUT).collect::<Vec<_>>();
+        for window in &[2, 5, 25, 100] {
+            assert_eq!(
+                first_invalid(numbers.iter().copied(), *window, BruteForce),
+                first_invalid(numbers.iter().copied(), *window, TwoPointer),
+            );
+        }
+    }
+
+    #[test]
+    fn validator_checks() {
+        let mut validator = Validator::new(3, TwoPointer);
+        let checks = [1, 2, 3, 5, 4, 9, 9, 18].iter().map(|&n| validator.push(n)).collect::<Vec<_>>();
+
+        assert_eq!(checks, vec![
+            Check::Preamble, Check::Preamble, Check::Preamble,
+            Check::Valid, Check::Invalid, Check::Valid, Check::Valid, Check::Valid,
+        ]);
+    }
+
+    #[test]
+    fn ranges() {
+        let numbers = [1, 2, 3, 4, 5];
+        assert_eq!(contiguous_range(&numbers, 9), Some(1..4));
+        assert_eq!(contiguous_range(&numbers, 5), Some(1..3));
+        assert_eq!(contiguous_range(&numbers, 4), None);
+        assert_eq!(contiguous_range(&numbers, 16), None);
+        assert_eq!(contiguous_range(&[], 0), None);
+    }
+
+    #[cfg(all(feature = "unstable", test))]
+    mod bench {
+        extern crate test;
+
+        use crate::*;
+        use test::Bencher;
+
+        #[bench]
+        fn d09p1_brute_force(b: &mut Bencher) {
+            b.iter(|| {
+                first_invalid(numbers(INPUT), WINDOW, BruteForce)
+            });
+        }
+
+        #[bench]
+        fn d09p1_two_pointer(b: &mut Bencher) {
+            b.iter(|| {
+                first_invalid(numbers(INPUT), WINDOW, TwoPointer)
+            });
+        }
+
+        #[bench]
+        fn d09p2(b: &mut Bencher) {
+            b.iter(|| {
+                solve_part_2(INPUT, WINDOW)
+            });
+        }
+    }
+}
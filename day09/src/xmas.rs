@@ -0,0 +1,90 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use day01::SearchAlg;
+
+/// Whether a number is the sum of two of the numbers before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// Part of the preamble, there aren't enough numbers before it to check
+    Preamble,
+    Valid,
+    Invalid,
+}
+
+/// Checks a stream of numbers, one at a time, against a sliding window of the numbers before them.
+///
+/// The window is kept sorted as well as in arrival order, so any [SearchAlg] can search it.
+pub struct Validator<A> {
+    size: usize,
+    search_alg: A,
+    recent: VecDeque<usize>,
+    sorted: Vec<usize>,
+}
+
+impl<A: SearchAlg> Validator<A> {
+    /// Panics if [size] is less than 2, no pair could be found in a smaller window
+    pub fn new(size: usize, search_alg: A) -> Validator<A> {
+        assert!(size >= 2, "a window needs at least two numbers, got {}", size);
+        Validator { size, search_alg, recent: VecDeque::with_capacity(size + 1), sorted: Vec::with_capacity(size + 1) }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Checks [n] against the window, then slides the window over it
+    pub fn push(&mut self, n: usize) -> Check {
+        let check = if self.recent.len() < self.size {
+            Check::Preamble
+        } else if self.search_alg.find_pair(&self.sorted, n).is_some() {
+            Check::Valid
+        } else {
+            Check::Invalid
+        };
+
+        self.recent.push_back(n);
+        let i = self.sorted.binary_search(&n).unwrap_or_else(|i| i);
+        self.sorted.insert(i, n);
+
+        if self.recent.len() > self.size {
+            let old = self.recent.pop_front().unwrap();
+            let i = self.sorted.binary_search(&old).unwrap();
+            self.sorted.remove(i);
+        }
+
+        check
+    }
+}
+
+/// The first of [numbers] that isn't the sum of two of the [size] numbers before it
+pub fn first_invalid<A: SearchAlg>(
+    numbers: impl IntoIterator<Item=usize>,
+    size: usize,
+    search_alg: A,
+) -> Option<usize> {
+    let mut validator = Validator::new(size, search_alg);
+    numbers.into_iter().find(|&n| validator.push(n) == Check::Invalid)
+}
+
+/// The range of at least two contiguous [numbers] summing to [target] that ends first.
+///
+/// Slides a window along the numbers, growing it while its sum is too small and shrinking it while
+/// its sum is too large, so every number is added and removed at most once.
+pub fn contiguous_range(numbers: &[usize], target: usize) -> Option<Range<usize>> {
+    let mut start = 0;
+    let mut sum = 0;
+
+    for (end, &n) in numbers.iter().enumerate() {
+        sum += n;
+        while sum > target && start < end {
+            sum -= numbers[start];
+            start += 1;
+        }
+        if sum == target && end > start {
+            return Some(start..end + 1);
+        }
+    }
+
+    None
+}
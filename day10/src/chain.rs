@@ -0,0 +1,162 @@
+use std::error::Error;
+use std::fmt;
+
+/// The largest difference in joltage an adapter accepts
+pub const MAX_STEP: u32 = 3;
+
+/// Every adapter in a bag sorted by joltage, between the outlet (0 jolts) and the device (3 jolts
+/// above the highest adapter)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chain {
+    joltages: Vec<u32>,
+}
+
+impl Chain {
+    /// Chains every adapter, failing if two consecutive joltages are more than [MAX_STEP] apart or
+    /// the device's joltage doesn't fit in a u32
+    pub fn new(adapters: &[u32]) -> Result<Chain, ChainError> {
+        let mut joltages = Vec::with_capacity(adapters.len() + 2);
+        joltages.push(0);
+        joltages.extend_from_slice(adapters);
+        joltages.sort_unstable();
+
+        let highest = *joltages.last().unwrap();
+        joltages.push(highest.checked_add(MAX_STEP).ok_or(ChainError::DeviceOverflow(highest))?);
+
+        match joltages.windows(2).find(|pair| pair[1] - pair[0] > MAX_STEP) {
+            Some(pair) => Err(ChainError::Gap { from: pair[0], to: pair[1] }),
+            None => Ok(Chain { joltages }),
+        }
+    }
+
+    /// Every joltage of the chain in order, from the outlet to the device
+    pub fn joltages(&self) -> &[u32] {
+        &self.joltages
+    }
+
+    pub fn device(&self) -> u32 {
+        *self.joltages.last().unwrap()
+    }
+
+    /// Differences between consecutive joltages
+    pub fn steps(&self) -> impl Iterator<Item=u32> + '_ {
+        self.joltages.windows(2).map(|pair| pair[1] - pair[0])
+    }
+
+    /// How many times each difference occurs, indexed by difference
+    pub fn distribution(&self) -> [usize; MAX_STEP as usize + 1] {
+        let mut distribution = [0; MAX_STEP as usize + 1];
+        for step in self.steps() {
+            distribution[step as usize] += 1;
+        }
+        distribution
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainError {
+    /// Two adapters too far apart to connect
+    Gap { from: u32, to: u32 },
+    /// The highest adapter, whose device would be rated past `u32::MAX` jolts
+    DeviceOverflow(u32),
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainError::Gap { from, to } => write!(f, "no adapter bridges {} to {} jolts", from, to),
+            ChainError::DeviceOverflow(highest) => {
+                write!(f, "a device {} jolts above the {} jolt adapter doesn't fit in 32 bits", MAX_STEP, highest)
+            }
+        }
+    }
+}
+
+impl Error for ChainError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountError {
+    /// More arrangements than fit in a u128
+    Overflow,
+    /// The algorithm can't handle this difference between consecutive joltages
+    UnsupportedStep(u32),
+}
+
+impl fmt::Display for CountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CountError::Overflow => f.write_str("too many arrangements to count in 128 bits"),
+            CountError::UnsupportedStep(step) => write!(f, "can't count arrangements with a step of {} jolts", step),
+        }
+    }
+}
+
+impl Error for CountError {}
+
+// ============================================================================================== //
+
+pub trait ArrangementAlg {
+    /// Number of distinct subsets of the adapters of [chain] that still connect the outlet to the device
+    fn count(&self, chain: &Chain) -> Result<u128, CountError>;
+}
+
+/// Counts, for every adapter, the ways of reaching it from the outlet: the sum of the ways of
+/// reaching each adapter at most [MAX_STEP] jolts below it
+pub struct DynamicProgramming;
+
+impl ArrangementAlg for DynamicProgramming {
+    fn count(&self, chain: &Chain) -> Result<u128, CountError> {
+        let joltages = chain.joltages();
+        let mut ways = vec![0u128; joltages.len()];
+        ways[0] = 1;
+
+        for i in 1..joltages.len() {
+            let mut sum = 0u128;
+            for j in (0..i).rev().take_while(|&j| joltages[i] - joltages[j] <= MAX_STEP) {
+                sum = sum.checked_add(ways[j]).ok_or(CountError::Overflow)?;
+            }
+            ways[i] = sum;
+        }
+
+        Ok(ways[joltages.len() - 1])
+    }
+}
+
+/// For chains whose steps are all 1 or 3 jolts. Every adapter next to a 3 jolt step is required,
+/// so the chain splits into independent runs of consecutive joltages. A run of n steps can be
+/// crossed in tribonacci(n) ways, and the runs multiply.
+pub struct Tribonacci;
+
+impl Tribonacci {
+    /// Ways of crossing a run of [steps] 1 jolt steps, with jumps of 1, 2 or 3
+    fn ways(steps: usize) -> Result<u128, CountError> {
+        let (mut a, mut b, mut c) = (0u128, 0u128, 1u128);
+        for _ in 0..steps {
+            let next = a.checked_add(b).and_then(|ab| ab.checked_add(c)).ok_or(CountError::Overflow)?;
+            a = b;
+            b = c;
+            c = next;
+        }
+        Ok(c)
+    }
+}
+
+impl ArrangementAlg for Tribonacci {
+    fn count(&self, chain: &Chain) -> Result<u128, CountError> {
+        let mut total = 1u128;
+        let mut run = 0;
+
+        for step in chain.steps() {
+            match step {
+                1 => run += 1,
+                3 => {
+                    total = total.checked_mul(Tribonacci::ways(run)?).ok_or(CountError::Overflow)?;
+                    run = 0;
+                }
+                _ => return Err(CountError::UnsupportedStep(step)),
+            }
+        }
+
+        Ok(total)
+    }
+}
@@ -0,0 +1,135 @@
+#![cfg_attr(feature = "unstable", feature(test))]
+
+pub use crate::chain::{ArrangementAlg, Chain, ChainError, CountError, DynamicProgramming, Tribonacci, MAX_STEP};
+
+mod chain;
+
+const INPUT: &str = include_str!("../input");
+
+/// One adapter joltage per line, panics on anything else
+pub fn parse(input: &str) -> Vec<u32> {
+    input.lines()
+        .map(|line| line.trim().parse().expect("failed to parse line"))
+        .collect()
+}
+
+/// Number of 1 jolt steps multiplied by the number of 3 jolt steps
+fn solve_part_1(chain: &Chain) -> usize {
+    let distribution = chain.distribution();
+    distribution[1] * distribution[3]
+}
+
+fn solve_part_2(chain: &Chain, alg: &impl ArrangementAlg) -> u128 {
+    alg.count(chain).unwrap()
+}
+
+pub fn solve_input(input: &str) -> (usize, u128) {
+    let chain = &Chain::new(&parse(input)).unwrap();
+    (solve_part_1(chain), solve_part_2(chain, &DynamicProgramming))
+}
+
+pub fn solve() -> (usize, u128) {
+    solve_input(INPUT)
+}
+
+// ============================================================================================== //
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const SAMPLE_01: &str = include_str!("../sample01");
+    const SAMPLE_02: &str = include_str!("../sample02");
+
+    fn chain(input: &str) -> Chain {
+        Chain::new(&parse(input)).unwrap()
+    }
+
+    #[test]
+    fn part_1_sample_input() {
+        assert_eq!(chain(SAMPLE_01).distribution(), [0, 7, 0, 5]);
+        assert_eq!(solve_part_1(&chain(SAMPLE_01)), 35);
+        assert_eq!(solve_part_1(&chain(SAMPLE_02)), 220);
+    }
+
+    #[test]
+    fn part_1_puzzle_input() {
+        assert_eq!(solve_part_1(&chain(INPUT)), 2240);
+    }
+
+    #[test]
+    fn part_2_sample_input() {
+        assert_eq!(solve_part_2(&chain(SAMPLE_01), &DynamicProgramming), 8);
+        assert_eq!(solve_part_2(&chain(SAMPLE_02), &DynamicProgramming), 19208);
+        assert_eq!(solve_part_2(&chain(SAMPLE_01), &Tribonacci), 8);
+        assert_eq!(solve_part_2(&chain(SAMPLE_02), &Tribonacci), 19208);
+    }
+
+    #[test]
+    fn part_2_puzzle_input() {
+        assert_eq!(solve_part_2(&chain(INPUT), &DynamicProgramming), 377801998336);
+        assert_eq!(solve_part_2(&chain(INPUT), &Tribonacci), 377801998336);
+    }
+
+    #[test]
+    fn chain_gaps() {
+        assert_eq!(Chain::new(&[1, 2, 6]), Err(ChainError::Gap { from: 2, to: 6 }));
+        assert_eq!(Chain::new(&[4]).unwrap_err().to_string(), "no adapter bridges 0 to 4 jolts");
+        assert_eq!(Chain::new(&[]).unwrap().joltages(), &[0, 3]);
+        assert_eq!(Chain::new(&[3, 1]).unwrap().device(), 6);
+        assert_eq!(Chain::new(&[u32::MAX]), Err(ChainError::DeviceOverflow(u32::MAX)));
+        assert_eq!(Chain::new(&[u32::MAX - 3]), Err(ChainError::Gap { from: 0, to: u32::MAX - 3 }));
+        assert_eq!(Chain::new(&[u32::MAX - 2]).unwrap_err().to_string(),
+                   "a device 3 jolts above the 4294967293 jolt adapter doesn't fit in 32 bits");
+    }
+
+    #[test]
+    fn two_jolt_steps() {
+        // 0, 2, 3, 5, 8: 2 and 3 are each optional, but not both
+        let chain = Chain::new(&[2, 3, 5]).unwrap();
+        assert_eq!(DynamicProgramming.count(&chain), Ok(3));
+        assert_eq!(Tribonacci.count(&chain), Err(CountError::UnsupportedStep(2)));
+    }
+
+    #[test]
+    fn overflow() {
+        // tribonacci(180) is about 1.2e47, more than a u128 holds
+        let chain = Chain::new(&(1..=180).collect::<Vec<_>>()).unwrap();
+        assert_eq!(DynamicProgramming.count(&chain), Err(CountError::Overflow));
+        assert_eq!(Tribonacci.count(&chain), Err(CountError::Overflow));
+
+        let chain = Chain::new(&(1..=100).collect::<Vec<_>>()).unwrap();
+        assert_eq!(DynamicProgramming.count(&chain), Tribonacci.count(&chain));
+    }
+
+    #[cfg(all(feature = "unstable", test))]
+    mod bench {
+        extern crate test;
+
+        use crate::*;
+        use test::Bencher;
+
+        #[bench]
+        fn d10_parse(b: &mut Bencher) {
+            b.iter(|| {
+                Chain::new(&parse(INPUT)).unwrap()
+            });
+        }
+
+        #[bench]
+        fn d10p2_dynamic_programming(b: &mut Bencher) {
+            let chain = &Chain::new(&parse(INPUT)).unwrap();
+            b.iter(|| {
+                DynamicProgramming.count(chain)
+            });
+        }
+
+        #[bench]
+        fn d10p2_tribonacci(b: &mut Bencher) {
+            let chain = &Chain::new(&parse(INPUT)).unwrap();
+            b.iter(|| {
+                Tribonacci.count(chain)
+            });
+        }
+    }
+}
@@ -0,0 +1,144 @@
+#![cfg_attr(feature = "unstable", feature(test))]
+
+use grid::Grid;
+
+pub use crate::seating::{Adjacent, Neighborhood, Rule, RunError, Simulation, Unstable, Visible, EMPTY, FLOOR, OCCUPIED};
+
+mod seating;
+
+const INPUT: &str = include_str!("../input");
+
+pub fn parse(input: &str) -> Grid {
+    Grid::parse(input).expect("failed to parse seat layout")
+}
+
+fn solve_part_1(layout: &Grid) -> usize {
+    Simulation::new(layout.clone(), &Rule::part_1()).run().unwrap()
+}
+
+fn solve_part_2(layout: &Grid) -> usize {
+    Simulation::new(layout.clone(), &Rule::part_2()).run().unwrap()
+}
+
+pub fn solve_input(input: &str) -> (usize, usize) {
+    let layout = &parse(input);
+    (solve_part_1(layout), solve_part_2(layout))
+}
+
+pub fn solve() -> (usize, usize) {
+    solve_input(INPUT)
+}
+
+// ============================================================================================== //
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const SAMPLE_01: &str = include_str!("../sample01");
+
+    #[test]
+    fn part_1_sample_input() {
+        assert_eq!(solve_part_1(&parse(SAMPLE_01)), 37);
+    }
+
+    #[test]
+    fn part_1_puzzle_input() {
+        assert_eq!(solve_part_1(&parse(INPUT)), 2171);
+    }
+
+    #[test]
+    fn part_2_sample_input() {
+        assert_eq!(solve_part_2(&parse(SAMPLE_01)), 26);
+    }
+
+    #[test]
+    fn part_2_puzzle_input() {
+        assert_eq!(solve_part_2(&parse(INPUT)), 1925);
+    }
+
+    #[test]
+    fn visible_neighbors() {
+        let layout = parse(".##.##.\n#.#.#.#\n##...##\n...L...\n##...##\n#.#.#.#\n.##.##.");
+        assert!(Visible.neighbors(&layout, 3, 3).is_empty());
+        assert!(Adjacent.neighbors(&layout, 3, 3).is_empty());
+
+        let layout = parse(".L.L.#.#.#.#.\n.............");
+        assert_eq!(Visible.neighbors(&layout, 1, 0), vec![layout.index(3, 0)]);
+        assert_eq!(Adjacent.neighbors(&layout, 1, 0), vec![]);
+    }
+
+    #[test]
+    fn configurable_tolerance() {
+        // with a tolerance of 1 a row of seats never settles
+        let layout = parse("LLLLL");
+        let mut simulation = Simulation::new(layout, &Rule { neighborhood: Adjacent, tolerance: 1 });
+        simulation.step();
+        assert_eq!(simulation.grid().to_string(), "#####");
+        simulation.step();
+        assert_eq!(simulation.grid().to_string(), "LLLLL");
+        assert_eq!(simulation.generation(), 2);
+        assert_eq!(simulation.run(), Err(Unstable { generation: 2, period: 2 }));
+
+        let mut simulation = Simulation::new(parse(SAMPLE_01), &Rule { neighborhood: Visible, tolerance: 9 });
+        assert_eq!(simulation.run(), Ok(71));
+    }
+
+    #[test]
+    fn frames() {
+        let mut out = vec![];
+        let frames = Simulation::new(parse("L.L\n..."), &Rule::part_1()).export_frames(&mut out).unwrap();
+
+        assert_eq!(frames, 2);
+        assert_eq!(String::from_utf8(out).unwrap(), "\
+generation 0: 0 occupied
+L.L
+...
+
+generation 1: 2 occupied
+#.#
+...
+");
+
+        let mut out = vec![];
+        let frames = Simulation::new(parse(SAMPLE_01), &Rule::part_1()).export_frames(&mut out).unwrap();
+        assert_eq!(frames, 6);
+        assert!(String::from_utf8(out).unwrap().ends_with("\
+generation 5: 37 occupied
+#.#L.L#.##
+#LLL#LL.L#
+L.#.L..#..
+#L##.##.L#
+#.#L.LL.LL
+#.#L#L#.##
+..L.L.....
+#L#L##L#L#
+#.LLLLLL.L
+#.#L#L#.##
+"));
+    }
+
+    #[cfg(all(feature = "unstable", test))]
+    mod bench {
+        extern crate test;
+
+        use crate::*;
+        use test::Bencher;
+
+        #[bench]
+        fn d11p1(b: &mut Bencher) {
+            let layout = &parse(INPUT);
+            b.iter(|| {
+                solve_part_1(layout)
+            });
+        }
+
+        #[bench]
+        fn d11p2(b: &mut Bencher) {
+            let layout = &parse(INPUT);
+            b.iter(|| {
+                solve_part_2(layout)
+            });
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+
+use grid::Grid;
+
+pub const FLOOR: u8 = b'.';
+pub const EMPTY: u8 = b'L';
+pub const OCCUPIED: u8 = b'#';
+
+/// The eight directions around a cell
+const DIRECTIONS: [(isize, isize); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+/// Decides which seats a person considers before sitting down or leaving
+pub trait Neighborhood {
+    /// Indices of the seats in [layout] that the seat at `(x, y)` looks at
+    fn neighbors(&self, layout: &Grid, x: usize, y: usize) -> Vec<usize>;
+}
+
+/// The seats in the eight cells around a seat
+pub struct Adjacent;
+
+impl Neighborhood for Adjacent {
+    fn neighbors(&self, layout: &Grid, x: usize, y: usize) -> Vec<usize> {
+        DIRECTIONS.iter()
+            .map(|&(dx, dy)| (x as isize + dx, y as isize + dy))
+            .filter(|&(x, y)| layout.try_get(x, y).is_some_and(|cell| cell != FLOOR))
+            .map(|(x, y)| layout.index(x as usize, y as usize))
+            .collect()
+    }
+}
+
+/// The first seat seen in each of the eight directions, looking past the floor
+pub struct Visible;
+
+impl Neighborhood for Visible {
+    fn neighbors(&self, layout: &Grid, x: usize, y: usize) -> Vec<usize> {
+        DIRECTIONS.iter()
+            .filter_map(|&(dx, dy)| {
+                (1..)
+                    .map(|distance| (x as isize + dx * distance, y as isize + dy * distance))
+                    .map(|(x, y)| (x, y, layout.try_get(x, y)))
+                    .take_while(|&(_, _, cell)| cell.is_some())
+                    .find(|&(_, _, cell)| cell != Some(FLOOR))
+                    .map(|(x, y, _)| layout.index(x as usize, y as usize))
+            })
+            .collect()
+    }
+}
+
+/// A [Neighborhood], and how many of its seats may be occupied before an occupied seat is left.
+/// An empty seat is taken once none of its neighbors are occupied.
+pub struct Rule<N> {
+    pub neighborhood: N,
+    pub tolerance: usize,
+}
+
+impl Rule<Adjacent> {
+    /// Part 1, people leave when four or more adjacent seats are occupied
+    pub fn part_1() -> Rule<Adjacent> {
+        Rule { neighborhood: Adjacent, tolerance: 4 }
+    }
+}
+
+impl Rule<Visible> {
+    /// Part 2, people leave when five or more visible seats are occupied
+    pub fn part_2() -> Rule<Visible> {
+        Rule { neighborhood: Visible, tolerance: 5 }
+    }
+}
+
+/// Seats people according to a [Rule] until nobody moves.
+///
+/// The layout never changes, so every seat's neighbors are found once up front and each
+/// generation only counts occupied neighbors.
+pub struct Simulation {
+    grid: Grid,
+    next: Vec<u8>,
+    /// Index of every seat, which is not floor
+    seats: Vec<usize>,
+    /// The neighbors of `seats[i]`
+    neighbors: Vec<Vec<usize>>,
+    tolerance: usize,
+    generation: usize,
+}
+
+impl Simulation {
+    pub fn new<N: Neighborhood>(layout: Grid, rule: &Rule<N>) -> Simulation {
+        let seats = (0..layout.cells().len())
+            .filter(|&i| layout.cells()[i] != FLOOR)
+            .collect::<Vec<_>>();
+        let neighbors = seats.iter()
+            .map(|&i| {
+                let (x, y) = layout.position(i);
+                rule.neighborhood.neighbors(&layout, x, y)
+            })
+            .collect();
+
+        Simulation {
+            next: layout.cells().to_vec(),
+            grid: layout,
+            seats,
+            neighbors,
+            tolerance: rule.tolerance,
+            generation: 0,
+        }
+    }
+
+    /// The current state of every seat
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// Number of steps taken so far
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn occupied(&self) -> usize {
+        self.grid.cells().iter().filter(|&&cell| cell == OCCUPIED).count()
+    }
+
+    /// Applies the rule to every seat at once, returns whether any seat changed
+    pub fn step(&mut self) -> bool {
+        let cells = self.grid.cells();
+        let mut changed = false;
+
+        for (&seat, neighbors) in self.seats.iter().zip(&self.neighbors) {
+            let occupied = neighbors.iter().filter(|&&i| cells[i] == OCCUPIED).count();
+            let cell = match cells[seat] {
+                EMPTY if occupied == 0 => OCCUPIED,
+                OCCUPIED if occupied >= self.tolerance => EMPTY,
+                cell => cell,
+            };
+            changed |= cell != cells[seat];
+            self.next[seat] = cell;
+        }
+
+        if changed {
+            self.grid.cells_mut().copy_from_slice(&self.next);
+            self.generation += 1;
+        }
+        changed
+    }
+
+    /// Steps until nobody moves, returns the number of occupied seats. Fails if the seats start
+    /// cycling through the same states instead, which some rules and layouts do.
+    pub fn run(&mut self) -> Result<usize, Unstable> {
+        self.run_with(|_| Ok(())).map_err(|e| match e {
+            RunError::Unstable(e) => e,
+            RunError::Io(_) => unreachable!(),
+        })
+    }
+
+    /// Like [run], writing every generation including the first and last to [out].
+    ///
+    /// Each frame is a `generation N: M occupied` line followed by the grid, frames are separated
+    /// by a blank line. Returns the number of frames written.
+    pub fn export_frames(&mut self, mut out: impl Write) -> Result<usize, RunError> {
+        let mut frames = 0;
+        self.run_with(|simulation| {
+            if frames > 0 {
+                writeln!(out)?;
+            }
+            writeln!(out, "generation {}: {} occupied\n{}", simulation.generation, simulation.occupied(), simulation.grid)?;
+            frames += 1;
+            Ok(())
+        })?;
+        Ok(frames)
+    }
+
+    /// Steps until stable, passing every generation to [on_frame]
+    fn run_with(&mut self, mut on_frame: impl FnMut(&Simulation) -> io::Result<()>) -> Result<usize, RunError> {
+        let mut seen = HashMap::new();
+
+        loop {
+            on_frame(self).map_err(RunError::Io)?;
+            if let Some(first) = seen.insert(self.grid.cells().to_vec(), self.generation) {
+                return Err(RunError::Unstable(Unstable { generation: first, period: self.generation - first }));
+            }
+
+            if !self.step() {
+                return Ok(self.occupied());
+            }
+        }
+    }
+}
+
+/// The seats never settle, they repeat the same [period] states forever from [generation] on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unstable {
+    pub generation: usize,
+    pub period: usize,
+}
+
+impl fmt::Display for Unstable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "seats cycle every {} generations from generation {} on", self.period, self.generation)
+    }
+}
+
+impl Error for Unstable {}
+
+#[derive(Debug)]
+pub enum RunError {
+    Unstable(Unstable),
+    Io(io::Error),
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Unstable(e) => e.fmt(f),
+            RunError::Io(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for RunError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RunError::Unstable(e) => Some(e),
+            RunError::Io(e) => Some(e),
+        }
+    }
+}
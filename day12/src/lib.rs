@@ -0,0 +1,132 @@
+#![cfg_attr(feature = "unstable", feature(test))]
+
+pub use crate::nav::{
+    navigate, parse, trajectory, trajectory_csv, Action, InstructionError, Mode, ParseError, Ship, Vector,
+};
+
+mod nav;
+
+const INPUT: &str = include_str!("../input");
+
+fn solve_part_1(actions: &[Action]) -> i64 {
+    navigate(actions, Mode::Direct).position().manhattan()
+}
+
+fn solve_part_2(actions: &[Action]) -> i64 {
+    navigate(actions, Mode::Waypoint).position().manhattan()
+}
+
+pub fn solve_input(input: &str) -> (i64, i64) {
+    let actions = &parse(input).unwrap();
+    (solve_part_1(actions), solve_part_2(actions))
+}
+
+pub fn solve() -> (i64, i64) {
+    solve_input(INPUT)
+}
+
+// ============================================================================================== //
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const SAMPLE_01: &str = include_str!("../sample01");
+
+    #[test]
+    fn part_1_sample_input() {
+        assert_eq!(solve_part_1(&parse(SAMPLE_01).unwrap()), 25);
+    }
+
+    #[test]
+    fn part_1_puzzle_input() {
+        assert_eq!(solve_part_1(&parse(INPUT).unwrap()), 530);
+    }
+
+    #[test]
+    fn part_2_sample_input() {
+        assert_eq!(solve_part_2(&parse(SAMPLE_01).unwrap()), 286);
+    }
+
+    #[test]
+    fn part_2_puzzle_input() {
+        assert_eq!(solve_part_2(&parse(INPUT).unwrap()), 876255);
+    }
+
+    #[test]
+    fn parse_actions() {
+        assert_eq!("R270".parse(), Ok(Action::Turn(-3)));
+        assert_eq!("L450".parse(), Ok(Action::Turn(5)));
+        assert_eq!("W7".parse(), Ok(Action::Move(Vector::WEST, 7)));
+        assert_eq!("R45".parse::<Action>(), Err(ParseError::NotRightAngle(45)));
+        assert_eq!("Fx".parse::<Action>(), Err(ParseError::InvalidValue("x".to_owned())));
+
+        let error = parse("F10\n\nX3").unwrap_err();
+        assert_eq!(error.to_string(), "line 3: unknown action 'X', expected one of N, S, E, W, L, R, F");
+    }
+
+    #[test]
+    fn rotations() {
+        let v = Vector::new(10, 4);
+        assert_eq!(v.rotate_left(1), Vector::new(-4, 10));
+        assert_eq!(v.rotate_left(-1), Vector::new(4, -10));
+        assert_eq!(v.rotate_left(2), Vector::new(-10, -4));
+
+        // any multiple of 90°, in either direction, lands on one of the four quarter turns
+        for turns in -12..=12 {
+            assert_eq!(v.rotate_left(turns), v.rotate_left(turns.rem_euclid(4)));
+            assert_eq!(v.rotate_left(turns).rotate_left(-turns), v);
+            assert_eq!(v.rotate_left(turns).manhattan(), v.manhattan());
+        }
+
+        let actions = parse("L90\nL180\nR630\nF1").unwrap();
+        assert_eq!(navigate(&actions, Mode::Direct).position(), Vector::new(1, 0));
+    }
+
+    #[test]
+    fn trajectory_export() {
+        let actions = parse(SAMPLE_01).unwrap();
+        assert_eq!(trajectory(&actions, Mode::Direct).len(), 6);
+
+        assert_eq!(trajectory_csv(&actions, Mode::Waypoint), "\
+step,action,x,y,vx,vy
+0,,0,0,10,1
+1,F10,100,10,10,1
+2,N3,100,10,10,4
+3,F7,170,38,10,4
+4,R90,170,38,4,-10
+5,F11,214,-72,4,-10
+");
+    }
+
+    #[cfg(all(feature = "unstable", test))]
+    mod bench {
+        extern crate test;
+
+        use crate::*;
+        use test::Bencher;
+
+        #[bench]
+        fn d12_parse(b: &mut Bencher) {
+            b.iter(|| {
+                parse(INPUT).unwrap()
+            });
+        }
+
+        #[bench]
+        fn d12p1(b: &mut Bencher) {
+            let actions = &parse(INPUT).unwrap();
+            b.iter(|| {
+                solve_part_1(actions)
+            });
+        }
+
+        #[bench]
+        fn d12p2(b: &mut Bencher) {
+            let actions = &parse(INPUT).unwrap();
+            b.iter(|| {
+                solve_part_2(actions)
+            });
+        }
+    }
+}
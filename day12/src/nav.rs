@@ -0,0 +1,238 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::Write;
+use std::ops::{Add, Mul};
+use std::str::FromStr;
+
+/// A position or displacement, with x growing east and y growing north
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Vector {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Vector {
+    pub const NORTH: Vector = Vector { x: 0, y: 1 };
+    pub const SOUTH: Vector = Vector { x: 0, y: -1 };
+    pub const EAST: Vector = Vector { x: 1, y: 0 };
+    pub const WEST: Vector = Vector { x: -1, y: 0 };
+
+    pub fn new(x: i64, y: i64) -> Vector {
+        Vector { x, y }
+    }
+
+    /// Distance from the origin moving only north/south and east/west
+    pub fn manhattan(self) -> i64 {
+        self.x.abs() + self.y.abs()
+    }
+
+    /// Rotated counterclockwise by [quarter_turns] × 90° around the origin, negative turns clockwise
+    pub fn rotate_left(self, quarter_turns: i64) -> Vector {
+        match quarter_turns.rem_euclid(4) {
+            0 => self,
+            1 => Vector { x: -self.y, y: self.x },
+            2 => Vector { x: -self.x, y: -self.y },
+            _ => Vector { x: self.y, y: -self.x },
+        }
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, rhs: Vector) -> Vector {
+        Vector { x: self.x + rhs.x, y: self.y + rhs.y }
+    }
+}
+
+impl Mul<i64> for Vector {
+    type Output = Vector;
+
+    fn mul(self, rhs: i64) -> Vector {
+        Vector { x: self.x * rhs, y: self.y * rhs }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// `N`, `S`, `E` or `W`, by a distance
+    Move(Vector, i64),
+    /// `L` or `R`, as counterclockwise quarter turns
+    Turn(i64),
+    /// `F`, by a number of times the ship's heading or waypoint
+    Forward(i64),
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Action::Move(Vector::NORTH, n) => write!(f, "N{}", n),
+            Action::Move(Vector::SOUTH, n) => write!(f, "S{}", n),
+            Action::Move(Vector::EAST, n) => write!(f, "E{}", n),
+            Action::Move(Vector::WEST, n) => write!(f, "W{}", n),
+            Action::Move(v, n) => write!(f, "({},{})*{}", v.x, v.y, n),
+            Action::Turn(turns) if turns < 0 => write!(f, "R{}", -turns * 90),
+            Action::Turn(turns) => write!(f, "L{}", turns * 90),
+            Action::Forward(n) => write!(f, "F{}", n),
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let action = s.chars().next().ok_or(ParseError::Empty)?;
+        let value = &s[action.len_utf8()..];
+        let n = value.parse::<i64>().map_err(|_| ParseError::InvalidValue(value.to_owned()))?;
+
+        let turn = |sign: i64| {
+            if n % 90 == 0 { Ok(Action::Turn(sign * n / 90)) } else { Err(ParseError::NotRightAngle(n)) }
+        };
+
+        match action {
+            'N' => Ok(Action::Move(Vector::NORTH, n)),
+            'S' => Ok(Action::Move(Vector::SOUTH, n)),
+            'E' => Ok(Action::Move(Vector::EAST, n)),
+            'W' => Ok(Action::Move(Vector::WEST, n)),
+            'L' => turn(1),
+            'R' => turn(-1),
+            'F' => Ok(Action::Forward(n)),
+            c => Err(ParseError::UnknownAction(c)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    UnknownAction(char),
+    InvalidValue(String),
+    /// A turn that isn't a multiple of 90°
+    NotRightAngle(i64),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => f.write_str("empty action"),
+            ParseError::UnknownAction(c) => write!(f, "unknown action {:?}, expected one of N, S, E, W, L, R, F", c),
+            ParseError::InvalidValue(value) => write!(f, "invalid value {:?}", value),
+            ParseError::NotRightAngle(degrees) => write!(f, "can only turn by multiples of 90°, not {}°", degrees),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// A [ParseError] in a list of navigation instructions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionError {
+    /// 1-based line of the action
+    pub line: usize,
+    pub error: ParseError,
+}
+
+impl fmt::Display for InstructionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl Error for InstructionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Parses one action per line, blank lines are skipped
+pub fn parse(input: &str) -> Result<Vec<Action>, InstructionError> {
+    input.lines().enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| line.parse().map_err(|error| InstructionError { line: i + 1, error }))
+        .collect()
+}
+
+// ============================================================================================== //
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// `N`/`S`/`E`/`W` move the ship, `L`/`R` turn it and `F` moves it along its heading.
+    /// The ship starts facing east.
+    Direct,
+    /// `N`/`S`/`E`/`W` move the waypoint, `L`/`R` rotate it around the ship and `F` moves the
+    /// ship to the waypoint that many times. The waypoint starts 10 east and 1 north of the ship.
+    Waypoint,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ship {
+    mode: Mode,
+    position: Vector,
+    /// The heading in [Mode::Direct], the waypoint relative to the ship in [Mode::Waypoint]
+    vector: Vector,
+}
+
+impl Ship {
+    pub fn new(mode: Mode) -> Ship {
+        let vector = match mode {
+            Mode::Direct => Vector::EAST,
+            Mode::Waypoint => Vector::new(10, 1),
+        };
+        Ship { mode, position: Vector::default(), vector }
+    }
+
+    pub fn position(&self) -> Vector {
+        self.position
+    }
+
+    /// The unit vector the ship faces in [Mode::Direct], the waypoint relative to the ship in
+    /// [Mode::Waypoint]
+    pub fn vector(&self) -> Vector {
+        self.vector
+    }
+
+    pub fn apply(&mut self, action: Action) {
+        match (action, self.mode) {
+            (Action::Move(direction, n), Mode::Direct) => self.position = self.position + direction * n,
+            (Action::Move(direction, n), Mode::Waypoint) => self.vector = self.vector + direction * n,
+            (Action::Turn(turns), _) => self.vector = self.vector.rotate_left(turns),
+            (Action::Forward(n), _) => self.position = self.position + self.vector * n,
+        }
+    }
+}
+
+/// The ship after following every action
+pub fn navigate(actions: &[Action], mode: Mode) -> Ship {
+    let mut ship = Ship::new(mode);
+    for &action in actions {
+        ship.apply(action);
+    }
+    ship
+}
+
+/// The ship before any action, followed by the ship after each action
+pub fn trajectory(actions: &[Action], mode: Mode) -> Vec<Ship> {
+    let mut ship = Ship::new(mode);
+    let mut ships = Vec::with_capacity(actions.len() + 1);
+    ships.push(ship);
+    for &action in actions {
+        ship.apply(action);
+        ships.push(ship);
+    }
+    ships
+}
+
+/// The [trajectory] as CSV with a header, one row per step. `vx`/`vy` is the heading or waypoint.
+pub fn trajectory_csv(actions: &[Action], mode: Mode) -> String {
+    let mut out = String::from("step,action,x,y,vx,vy\n");
+    let ships = trajectory(actions, mode);
+    let labels = std::iter::once(String::new()).chain(actions.iter().map(Action::to_string));
+
+    for (step, (ship, label)) in ships.iter().zip(labels).enumerate() {
+        let (position, vector) = (ship.position(), ship.vector());
+        writeln!(out, "{},{},{},{},{},{}", step, label, position.x, position.y, vector.x, vector.y).unwrap();
+    }
+    out
+}
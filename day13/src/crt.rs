@@ -0,0 +1,206 @@
+//! Systems of linear congruences, solved with the generalized Chinese Remainder Theorem.
+//!
+//! Moduli don't need to be pairwise coprime, two congruences are compatible when their residues
+//! agree modulo the gcd of their moduli. Combining congruences checks its products, so results
+//! that don't fit in an i128 are reported as [CrtError::Overflow] rather than silently wrapping.
+//! [extended_gcd] and [mod_inverse] work on plain i128s and assert their inputs are in range
+//! instead, which every modulus and reduced residue is.
+
+use std::error::Error;
+use std::fmt;
+
+/// `x ≡ residue (mod modulus)`, with `0 <= residue < modulus`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Congruence {
+    pub residue: i128,
+    pub modulus: i128,
+}
+
+impl Congruence {
+    /// Reduces [residue], which may be negative, modulo [modulus]. Panics if [modulus] isn't positive.
+    pub fn new(residue: i128, modulus: i128) -> Congruence {
+        assert!(modulus > 0, "modulus must be positive, got {}", modulus);
+        Congruence { residue: residue.rem_euclid(modulus), modulus }
+    }
+
+    /// Every integer, `x ≡ 0 (mod 1)`
+    pub fn any() -> Congruence {
+        Congruence { residue: 0, modulus: 1 }
+    }
+
+    pub fn contains(&self, x: i128) -> bool {
+        x.rem_euclid(self.modulus) == self.residue
+    }
+}
+
+impl fmt::Display for Congruence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "x ≡ {} (mod {})", self.residue, self.modulus)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrtError {
+    /// The congruences contradict each other
+    NoSolution,
+    /// The combined modulus, or an intermediate value, doesn't fit in an i128
+    Overflow,
+}
+
+impl fmt::Display for CrtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CrtError::NoSolution => f.write_str("the congruences have no common solution"),
+            CrtError::Overflow => f.write_str("overflowed 128 bit arithmetic"),
+        }
+    }
+}
+
+impl Error for CrtError {}
+
+/// `(g, x, y)` with `g = gcd(a, b) >= 0` and `a·x + b·y = g`.
+///
+/// Panics if [a] or [b] is `i128::MIN`, whose gcd with -1 or 0 doesn't fit. Past that every
+/// remainder and coefficient is at most `max(|a|, |b|)`, so nothing overflows.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    assert!(a != i128::MIN && b != i128::MIN, "extended_gcd operands can't be i128::MIN");
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (1, 0);
+    let (mut old_t, mut t) = (0, 1);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+        (old_t, t) = (t, old_t - q * t);
+    }
+
+    if old_r < 0 { (-old_r, -old_s, -old_t) } else { (old_r, old_s, old_t) }
+}
+
+/// `x` with `a·x ≡ 1 (mod m)`, `None` unless [a] and [m] are coprime. Panics if [m] isn't positive.
+pub fn mod_inverse(a: i128, m: i128) -> Option<i128> {
+    assert!(m > 0, "modulus must be positive, got {}", m);
+    match extended_gcd(a.rem_euclid(m), m) {
+        (1, x, _) => Some(x.rem_euclid(m)),
+        _ => None,
+    }
+}
+
+/// The integers satisfying both [a] and [b]
+pub fn combine(a: Congruence, b: Congruence) -> Result<Congruence, CrtError> {
+    // x = a.residue + a.modulus·t, so a.modulus·t ≡ b.residue - a.residue (mod b.modulus)
+    let t = solve_linear(a.modulus, b.residue - a.residue, b.modulus)?;
+
+    let modulus = a.modulus.checked_mul(t.modulus).ok_or(CrtError::Overflow)?;
+    let residue = a.modulus.checked_mul(t.residue)
+        .and_then(|x| x.checked_add(a.residue))
+        .ok_or(CrtError::Overflow)?;
+    Ok(Congruence::new(residue, modulus))
+}
+
+/// The integers satisfying every congruence, `x ≡ 0 (mod 1)` if there are none
+pub fn solve(congruences: impl IntoIterator<Item=Congruence>) -> Result<Congruence, CrtError> {
+    congruences.into_iter().try_fold(Congruence::any(), combine)
+}
+
+/// Solves `a·x ≡ b (mod m)`.
+///
+/// With `g = gcd(a, m)` there's a solution only if `g` divides `b`, in which case the solutions
+/// are a single congruence modulo `m / g`.
+pub fn solve_linear(a: i128, b: i128, m: i128) -> Result<Congruence, CrtError> {
+    assert!(m > 0, "modulus must be positive, got {}", m);
+    let (g, _, _) = extended_gcd(a.rem_euclid(m), m);
+
+    if b.rem_euclid(g) != 0 {
+        return Err(CrtError::NoSolution);
+    }
+
+    let m = m / g;
+    let inverse = mod_inverse(a / g, m).unwrap();
+    let x = (b / g).rem_euclid(m).checked_mul(inverse).ok_or(CrtError::Overflow)?;
+    Ok(Congruence::new(x, m))
+}
+
+// ============================================================================================== //
+
+#[cfg(test)]
+mod tests {
+    use crate::crt::*;
+
+    #[test]
+    fn gcd_and_inverse() {
+        assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+        assert_eq!(extended_gcd(-240, 46).0, 2);
+        assert_eq!(extended_gcd(0, 7), (7, 0, 1));
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+        // i128::MIN ≡ 5 (mod 7)
+        assert_eq!(mod_inverse(i128::MIN, 7), Some(3));
+        assert_eq!(mod_inverse(5, 1), Some(0));
+        assert_eq!(extended_gcd(i128::MAX, -1), (1, 0, -1));
+        assert_eq!(extended_gcd(i128::MAX, i128::MAX - 1), (1, 1, -1));
+    }
+
+    #[test]
+    #[should_panic(expected = "can't be i128::MIN")]
+    fn gcd_of_min() {
+        extended_gcd(i128::MIN, -1);
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus must be positive, got 0")]
+    fn inverse_modulo_zero() {
+        mod_inverse(3, 0);
+    }
+
+    #[test]
+    fn coprime_moduli() {
+        let x = solve(vec![Congruence::new(2, 3), Congruence::new(3, 5), Congruence::new(2, 7)]);
+        assert_eq!(x, Ok(Congruence::new(23, 105)));
+        assert_eq!(x.unwrap().to_string(), "x ≡ 23 (mod 105)");
+        assert_eq!(solve(vec![]), Ok(Congruence::any()));
+    }
+
+    #[test]
+    fn non_coprime_moduli() {
+        // x ≡ 2 (mod 4) and x ≡ 4 (mod 6) agree mod gcd 2, so x ≡ 10 (mod 12)
+        assert_eq!(combine(Congruence::new(2, 4), Congruence::new(4, 6)), Ok(Congruence::new(10, 12)));
+        // x ≡ 1 (mod 4) and x ≡ 2 (mod 6) disagree on parity
+        assert_eq!(combine(Congruence::new(1, 4), Congruence::new(2, 6)), Err(CrtError::NoSolution));
+        // a modulus dividing another either adds nothing or contradicts
+        assert_eq!(combine(Congruence::new(5, 12), Congruence::new(1, 4)), Ok(Congruence::new(5, 12)));
+        assert_eq!(combine(Congruence::new(5, 12), Congruence::new(0, 4)), Err(CrtError::NoSolution));
+    }
+
+    #[test]
+    fn negative_residues() {
+        assert_eq!(Congruence::new(-1, 7), Congruence { residue: 6, modulus: 7 });
+        assert!(Congruence::new(-1, 7).contains(-8));
+
+        let x = solve(vec![Congruence::new(0, 17), Congruence::new(-2, 13), Congruence::new(-3, 19)]).unwrap();
+        assert_eq!(x.residue, 3417);
+    }
+
+    #[test]
+    fn linear_congruences() {
+        assert_eq!(solve_linear(3, 2, 7), Ok(Congruence::new(3, 7)));
+        // 6x ≡ 4 (mod 10) reduces to 3x ≡ 2 (mod 5)
+        assert_eq!(solve_linear(6, 4, 10), Ok(Congruence::new(4, 5)));
+        assert_eq!(solve_linear(6, 3, 10), Err(CrtError::NoSolution));
+        assert_eq!(solve_linear(0, 0, 5), Ok(Congruence::any()));
+    }
+
+    #[test]
+    fn overflow() {
+        let big = (1 << 100) + 1;
+        let other = (1 << 100) - 1;
+        assert_eq!(combine(Congruence::new(1, big), Congruence::new(2, other)), Err(CrtError::Overflow));
+
+        let p = 1_000_000_007;
+        let q = 998_244_353;
+        let x = combine(Congruence::new(5, p), Congruence::new(7, q)).unwrap();
+        assert!(Congruence::new(5, p).contains(x.residue) && Congruence::new(7, q).contains(x.residue));
+    }
+}
@@ -0,0 +1,122 @@
+#![cfg_attr(feature = "unstable", feature(test))]
+
+use crate::crt::Congruence;
+
+pub mod crt;
+
+const INPUT: &str = include_str!("../input");
+
+/// The notes: the earliest departure, then the bus ids in schedule order, `x` for out of service
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notes {
+    pub earliest: i128,
+    /// (offset in the schedule, bus id) of every bus in service
+    pub buses: Vec<(i128, i128)>,
+}
+
+impl Notes {
+    /// Panics if the notes are malformed
+    pub fn parse(input: &str) -> Notes {
+        let mut lines = input.lines();
+        let earliest = lines.next().and_then(|line| line.trim().parse().ok()).expect("failed to parse timestamp");
+        let buses = lines.next().expect("missing bus schedule")
+            .trim()
+            .split(',')
+            .enumerate()
+            .filter(|&(_, id)| id != "x")
+            .map(|(offset, id)| (offset as i128, id.parse().expect("failed to parse bus id")))
+            .collect();
+
+        Notes { earliest, buses }
+    }
+}
+
+/// The id of the first bus leaving at or after the earliest departure, multiplied by the wait
+fn solve_part_1(notes: &Notes) -> i128 {
+    notes.buses.iter()
+        .map(|&(_, id)| ((id - notes.earliest.rem_euclid(id)) % id, id))
+        .min()
+        .map(|(wait, id)| wait * id)
+        .expect("no bus in service")
+}
+
+/// The earliest timestamp where every bus leaves its offset in minutes later, that is the
+/// timestamp is congruent to `-offset` modulo each bus id
+fn solve_part_2(notes: &Notes) -> i128 {
+    let congruences = notes.buses.iter().map(|&(offset, id)| Congruence::new(-offset, id));
+    crt::solve(congruences).unwrap().residue
+}
+
+pub fn solve_input(input: &str) -> (i128, i128) {
+    let notes = &Notes::parse(input);
+    (solve_part_1(notes), solve_part_2(notes))
+}
+
+pub fn solve() -> (i128, i128) {
+    solve_input(INPUT)
+}
+
+// ============================================================================================== //
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const SAMPLE_01: &str = include_str!("../sample01");
+
+    #[test]
+    fn part_1_sample_input() {
+        assert_eq!(solve_part_1(&Notes::parse(SAMPLE_01)), 295);
+    }
+
+    #[test]
+    fn part_1_puzzle_input() {
+        assert_eq!(solve_part_1(&Notes::parse(INPUT)), 222);
+    }
+
+    #[test]
+    fn part_2_sample_input() {
+        assert_eq!(solve_part_2(&Notes::parse(SAMPLE_01)), 1068781);
+
+        let schedules = [("67,7,59,61", 754018), ("67,x,7,59,61", 779210), ("1789,37,47,1889", 1202161486)];
+        for &(schedule, expected) in &schedules {
+            assert_eq!(solve_part_2(&Notes::parse(&format!("0\n{}", schedule))), expected);
+        }
+    }
+
+    #[test]
+    fn part_2_puzzle_input() {
+        assert_eq!(solve_part_2(&Notes::parse(INPUT)), 1207874408578573);
+    }
+
+    #[test]
+    fn parse_notes() {
+        let notes = Notes::parse(SAMPLE_01);
+        assert_eq!(notes.earliest, 939);
+        assert_eq!(notes.buses, vec![(0, 7), (1, 13), (4, 59), (6, 31), (7, 19)]);
+    }
+
+    #[cfg(all(feature = "unstable", test))]
+    mod bench {
+        extern crate test;
+
+        use crate::*;
+        use test::Bencher;
+
+        #[bench]
+        fn d13p1(b: &mut Bencher) {
+            let notes = &Notes::parse(INPUT);
+            b.iter(|| {
+                solve_part_1(notes)
+            });
+        }
+
+        #[bench]
+        fn d13p2(b: &mut Bencher) {
+            let notes = &Notes::parse(INPUT);
+            b.iter(|| {
+                solve_part_2(notes)
+            });
+        }
+    }
+}
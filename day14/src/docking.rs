@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Values and addresses are 36 bit unsigned integers
+pub const WORD_BITS: u32 = 36;
+pub const WORD_MAX: u64 = (1 << WORD_BITS) - 1;
+
+/// A bitmask like `XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X`, most significant bit first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mask {
+    /// The `1` bits
+    pub ones: u64,
+    /// The `X` bits, every other bit is a `0`
+    pub floating: u64,
+}
+
+impl Mask {
+    /// The mask in effect before the first `mask` instruction, every bit is an `X`
+    pub const IDENTITY: Mask = Mask { ones: 0, floating: WORD_MAX };
+
+    /// Part 1, `0`/`1` overwrite the bit of the value and `X` leaves it unchanged
+    pub fn apply_to_value(&self, value: u64) -> u64 {
+        value & self.floating | self.ones
+    }
+
+    /// Part 2, `1` overwrites the bit of the address, `0` leaves it unchanged and `X` takes both
+    /// values
+    pub fn apply_to_address(&self, address: u64) -> AddressSet {
+        AddressSet { fixed: (address | self.ones) & !self.floating, floating: self.floating }
+    }
+}
+
+impl fmt::Display for Mask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for bit in (0..WORD_BITS).rev() {
+            let c = match (self.ones >> bit & 1, self.floating >> bit & 1) {
+                (_, 1) => 'X',
+                (1, _) => '1',
+                _ => '0',
+            };
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Mask {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != WORD_BITS as usize {
+            return Err(ParseError::InvalidMask(s.to_owned()));
+        }
+
+        s.bytes().try_fold(Mask { ones: 0, floating: 0 }, |mask, b| {
+            let (one, floating) = match b {
+                b'0' => (0, 0),
+                b'1' => (1, 0),
+                b'X' => (0, 1),
+                _ => return Err(ParseError::InvalidMask(s.to_owned())),
+            };
+            Ok(Mask { ones: mask.ones << 1 | one, floating: mask.floating << 1 | floating })
+        })
+    }
+}
+
+/// Every address matching [fixed] outside the [floating] bits, `2^floating.count_ones()` of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AddressSet {
+    /// The value of every bit that isn't floating, floating bits are 0
+    pub fixed: u64,
+    pub floating: u64,
+}
+
+impl AddressSet {
+    pub fn single(address: u64) -> AddressSet {
+        AddressSet { fixed: address, floating: 0 }
+    }
+
+    /// Number of addresses in the set
+    pub fn count(&self) -> u64 {
+        1 << self.floating.count_ones()
+    }
+
+    pub fn contains(&self, address: u64) -> bool {
+        address & !self.floating == self.fixed
+    }
+
+    /// Whether some address is in both sets, which is when they agree on the bits fixed in both
+    pub fn intersects(&self, other: &AddressSet) -> bool {
+        (self.fixed ^ other.fixed) & !self.floating & !other.floating == 0
+    }
+
+    /// The addresses in [self] but not in [other], as disjoint sets.
+    ///
+    /// Each bit floating here but fixed in [other] splits off the addresses that disagree with
+    /// [other] on it, so there are at most as many sets as such bits.
+    pub fn difference(&self, other: &AddressSet) -> Vec<AddressSet> {
+        if !self.intersects(other) {
+            return vec![*self];
+        }
+
+        let mut remaining = *self;
+        let mut split = self.floating & !other.floating;
+        let mut sets = Vec::with_capacity(split.count_ones() as usize);
+
+        while split != 0 {
+            let bit = split & split.wrapping_neg();
+            split &= !bit;
+            remaining.floating &= !bit;
+            sets.push(AddressSet { fixed: remaining.fixed | !other.fixed & bit, floating: remaining.floating });
+            remaining.fixed |= other.fixed & bit;
+        }
+        sets
+    }
+
+    /// Every address in the set, in increasing order
+    pub fn addresses(&self) -> impl Iterator<Item=u64> {
+        let AddressSet { fixed, floating } = *self;
+        // counts through the subsets of floating, 0 last after wrapping around
+        let mut subset = Some(0u64);
+        std::iter::from_fn(move || {
+            let current = subset?;
+            let next = current.wrapping_sub(floating) & floating;
+            subset = if next == 0 { None } else { Some(next) };
+            Some(fixed | current)
+        })
+    }
+}
+
+impl fmt::Display for AddressSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Mask { ones: self.fixed, floating: self.floating })
+    }
+}
+
+// ============================================================================================== //
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    /// `mask = ...`
+    Mask(Mask),
+    /// `mem[address] = value`
+    Write { address: u64, value: u64 },
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Mask(mask) => write!(f, "mask = {}", mask),
+            Instruction::Write { address, value } => write!(f, "mem[{}] = {}", address, value),
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (target, value) = s.split_once(" = ").ok_or_else(|| ParseError::InvalidInstruction(s.to_owned()))?;
+
+        if target == "mask" {
+            return value.parse().map(Instruction::Mask);
+        }
+
+        let address = target.strip_prefix("mem[")
+            .and_then(|target| target.strip_suffix(']'))
+            .ok_or_else(|| ParseError::InvalidInstruction(s.to_owned()))?;
+
+        Ok(Instruction::Write { address: parse_word(address)?, value: parse_word(value)? })
+    }
+}
+
+fn parse_word(s: &str) -> Result<u64, ParseError> {
+    match s.parse::<u64>() {
+        Ok(n) if n <= WORD_MAX => Ok(n),
+        _ => Err(ParseError::InvalidNumber(s.to_owned())),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidInstruction(String),
+    /// Not 36 characters of `0`, `1` or `X`
+    InvalidMask(String),
+    /// Not a 36 bit unsigned integer
+    InvalidNumber(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidInstruction(s) => write!(f, "invalid instruction {:?}", s),
+            ParseError::InvalidMask(s) => write!(f, "invalid mask {:?}, expected {} of '0', '1' or 'X'", s, WORD_BITS),
+            ParseError::InvalidNumber(s) => write!(f, "invalid number {:?}, expected a {} bit unsigned integer", s, WORD_BITS),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// A [ParseError] in the initialization program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramError {
+    /// 1-based line of the instruction
+    pub line: usize,
+    pub error: ParseError,
+}
+
+impl fmt::Display for ProgramError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl Error for ProgramError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Parses one instruction per line, blank lines are skipped
+pub fn parse(input: &str) -> Result<Vec<Instruction>, ProgramError> {
+    input.lines().enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| line.trim().parse().map_err(|error| ProgramError { line: i + 1, error }))
+        .collect()
+}
+
+// ============================================================================================== //
+
+/// Memory for the version 1 decoder chip, where the mask applies to values.
+///
+/// Only written addresses are stored, the rest are 0.
+#[derive(Debug, Clone, Default)]
+pub struct ValueMemory {
+    mask: Option<Mask>,
+    cells: HashMap<u64, u64>,
+}
+
+impl ValueMemory {
+    pub fn new() -> ValueMemory {
+        ValueMemory::default()
+    }
+
+    pub fn execute(&mut self, instruction: Instruction) {
+        match instruction {
+            Instruction::Mask(mask) => self.mask = Some(mask),
+            Instruction::Write { address, value } => {
+                let mask = self.mask.unwrap_or(Mask::IDENTITY);
+                self.cells.insert(address, mask.apply_to_value(value));
+            }
+        }
+    }
+
+    pub fn get(&self, address: u64) -> u64 {
+        self.cells.get(&address).copied().unwrap_or(0)
+    }
+
+    /// Sum of every value in memory
+    pub fn sum(&self) -> u64 {
+        self.cells.values().sum()
+    }
+}
+
+/// Memory for the version 2 decoder chip, where the mask applies to addresses.
+///
+/// A write with `n` floating bits touches `2^n` addresses, so rather than storing each of them
+/// memory is a list of disjoint [AddressSet]s sharing a value. A write removes its addresses from
+/// every set it overlaps, splitting them, then adds its own set.
+#[derive(Debug, Clone, Default)]
+pub struct FloatingMemory {
+    mask: Option<Mask>,
+    cells: Vec<(AddressSet, u64)>,
+}
+
+impl FloatingMemory {
+    pub fn new() -> FloatingMemory {
+        FloatingMemory::default()
+    }
+
+    pub fn execute(&mut self, instruction: Instruction) {
+        match instruction {
+            Instruction::Mask(mask) => self.mask = Some(mask),
+            Instruction::Write { address, value } => {
+                let written = self.mask.unwrap_or(Mask::IDENTITY).apply_to_address(address);
+                self.cells = self.cells.iter()
+                    .flat_map(|&(set, value)| set.difference(&written).into_iter().map(move |set| (set, value)))
+                    .filter(|&(_, value)| value != 0)
+                    .collect();
+                if value != 0 {
+                    self.cells.push((written, value));
+                }
+            }
+        }
+    }
+
+    pub fn get(&self, address: u64) -> u64 {
+        self.cells.iter()
+            .find(|(set, _)| set.contains(address))
+            .map_or(0, |&(_, value)| value)
+    }
+
+    /// The disjoint sets of addresses holding a nonzero value
+    pub fn sets(&self) -> &[(AddressSet, u64)] {
+        &self.cells
+    }
+
+    /// Sum of every value in memory, which can exceed 64 bits when many bits float
+    pub fn sum(&self) -> u128 {
+        self.cells.iter().map(|&(set, value)| set.count() as u128 * value as u128).sum()
+    }
+}
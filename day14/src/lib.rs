@@ -0,0 +1,163 @@
+#![cfg_attr(feature = "unstable", feature(test))]
+
+pub use crate::docking::{
+    parse, AddressSet, FloatingMemory, Instruction, Mask, ParseError, ProgramError, ValueMemory, WORD_BITS, WORD_MAX,
+};
+
+mod docking;
+
+const INPUT: &str = include_str!("../input");
+
+fn solve_part_1(program: &[Instruction]) -> u64 {
+    let mut memory = ValueMemory::new();
+    for &instruction in program {
+        memory.execute(instruction);
+    }
+    memory.sum()
+}
+
+fn solve_part_2(program: &[Instruction]) -> u128 {
+    let mut memory = FloatingMemory::new();
+    for &instruction in program {
+        memory.execute(instruction);
+    }
+    memory.sum()
+}
+
+pub fn solve_input(input: &str) -> (u64, u128) {
+    let program = &parse(input).unwrap();
+    (solve_part_1(program), solve_part_2(program))
+}
+
+pub fn solve() -> (u64, u128) {
+    solve_input(INPUT)
+}
+
+// ============================================================================================== //
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::*;
+
+    const SAMPLE_01: &str = include_str!("../sample01");
+    const SAMPLE_02: &str = include_str!("../sample02");
+
+    #[test]
+    fn part_1_sample_input() {
+        assert_eq!(solve_part_1(&parse(SAMPLE_01).unwrap()), 165);
+    }
+
+    #[test]
+    fn part_1_puzzle_input() {
+        assert_eq!(solve_part_1(&parse(INPUT).unwrap()), 10059665738380);
+    }
+
+    #[test]
+    fn part_2_sample_input() {
+        assert_eq!(solve_part_2(&parse(SAMPLE_02).unwrap()), 208);
+    }
+
+    #[test]
+    fn part_2_puzzle_input() {
+        assert_eq!(solve_part_2(&parse(INPUT).unwrap()), 25247753300024);
+    }
+
+    #[test]
+    fn parse_instructions() {
+        let program = parse(SAMPLE_01).unwrap();
+        assert_eq!(program[0], Instruction::Mask(Mask { ones: 0b100_0000, floating: WORD_MAX & !0b100_0010 }));
+        assert_eq!(program[1], Instruction::Write { address: 8, value: 11 });
+        assert_eq!(program.iter().map(Instruction::to_string).collect::<Vec<_>>().join("\n"), SAMPLE_01.trim_end());
+
+        assert_eq!("mem[68719476736] = 1".parse::<Instruction>(), Err(ParseError::InvalidNumber("68719476736".to_owned())));
+        assert_eq!("mask = 1X0".parse::<Instruction>(), Err(ParseError::InvalidMask("1X0".to_owned())));
+        let error = parse("mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX\nmem[8 = 11").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: invalid instruction \"mem[8 = 11\"");
+    }
+
+    #[test]
+    fn address_set_difference() {
+        let mask: Mask = "00000000000000000000000000000000XXXX".parse().unwrap();
+        let all = mask.apply_to_address(0);
+        let written = "0000000000000000000000000000000010X1".parse::<Mask>().unwrap().apply_to_address(0);
+
+        let rest = all.difference(&written);
+        assert_eq!(rest.iter().map(AddressSet::count).sum::<u64>(), 16 - 2);
+        for address in all.addresses() {
+            let pieces = rest.iter().filter(|set| set.contains(address)).count();
+            assert_eq!(pieces, if written.contains(address) { 0 } else { 1 }, "address {}", address);
+        }
+
+        assert_eq!(all.addresses().collect::<Vec<_>>(), (0..16).collect::<Vec<_>>());
+        assert_eq!(AddressSet::single(5).addresses().collect::<Vec<_>>(), vec![5]);
+        assert_eq!(AddressSet::single(5).difference(&AddressSet::single(4)), vec![AddressSet::single(5)]);
+        assert_eq!(AddressSet::single(5).difference(&all), vec![]);
+    }
+
+    #[test]
+    fn floating_matches_expansion() {
+        // writes every address of every set one by one, as the puzzle describes
+        let program = parse(INPUT).unwrap();
+        let mut expanded = HashMap::new();
+        let mut mask = Mask::IDENTITY;
+        for &instruction in &program {
+            match instruction {
+                Instruction::Mask(m) => mask = m,
+                Instruction::Write { address, value } => {
+                    for address in mask.apply_to_address(address).addresses() {
+                        expanded.insert(address, value);
+                    }
+                }
+            }
+        }
+
+        let mut memory = FloatingMemory::new();
+        for &instruction in &program {
+            memory.execute(instruction);
+        }
+        assert_eq!(memory.sum(), expanded.values().map(|&v| v as u128).sum());
+        for (&address, &value) in expanded.iter().take(1000) {
+            assert_eq!(memory.get(address), value);
+        }
+    }
+
+    #[test]
+    fn many_floating_bits() {
+        // 34 floating bits, 2^34 addresses per write. Both writes to 8 land on the same set, which
+        // 7 doesn't touch since it differs on the bit the mask leaves unchanged.
+        let mut memory = FloatingMemory::new();
+        for instruction in parse(SAMPLE_01).unwrap() {
+            memory.execute(instruction);
+        }
+        assert_eq!(memory.sum(), 101 << 34);
+        assert_eq!(memory.sets().len(), 1);
+        assert_eq!(memory.get(0b1000110), 101);
+        assert_eq!(memory.get(0b1000100), 0);
+    }
+
+    #[cfg(all(feature = "unstable", test))]
+    mod bench {
+        extern crate test;
+
+        use crate::*;
+        use test::Bencher;
+
+        #[bench]
+        fn d14p1(b: &mut Bencher) {
+            let program = &parse(INPUT).unwrap();
+            b.iter(|| {
+                solve_part_1(program)
+            });
+        }
+
+        #[bench]
+        fn d14p2(b: &mut Bencher) {
+            let program = &parse(INPUT).unwrap();
+            b.iter(|| {
+                solve_part_2(program)
+            });
+        }
+    }
+}
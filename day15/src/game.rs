@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+/// Remembers the last turn each number was spoken on
+pub trait History {
+    /// A history for a game of [turns] turns
+    fn with_turns(turns: u32) -> Self;
+
+    /// Records [number] as spoken on [turn], returns the turn it was last spoken on before
+    fn replace(&mut self, number: u32, turn: u32) -> Option<u32>;
+}
+
+/// A turn per number, indexed by the number.
+///
+/// Every number spoken after the starting ones is the age of another number, so is smaller than
+/// the number of turns. One flat allocation covers them all, 0 marking numbers never spoken since
+/// turns start at 1. Numbers past the table, which only starting numbers can be, are kept in a
+/// side map rather than growing the table to their size.
+pub struct FlatHistory {
+    turns: Vec<u32>,
+    large: HashMap<u32, u32>,
+}
+
+impl History for FlatHistory {
+    fn with_turns(turns: u32) -> Self {
+        FlatHistory { turns: vec![0; turns as usize], large: HashMap::new() }
+    }
+
+    fn replace(&mut self, number: u32, turn: u32) -> Option<u32> {
+        match self.turns.get_mut(number as usize) {
+            Some(last) => match std::mem::replace(last, turn) {
+                0 => None,
+                previous => Some(previous),
+            },
+            None => self.large.insert(number, turn),
+        }
+    }
+}
+
+/// Only the numbers spoken so far, which for long games is most of them
+pub struct HashHistory(HashMap<u32, u32>);
+
+impl History for HashHistory {
+    fn with_turns(_: u32) -> Self {
+        HashHistory(HashMap::new())
+    }
+
+    fn replace(&mut self, number: u32, turn: u32) -> Option<u32> {
+        self.0.insert(number, turn)
+    }
+}
+
+/// The number spoken on turn [n], counting from 1, using a [FlatHistory].
+///
+/// `None` if there are no starting numbers or [n] is 0.
+pub fn nth_spoken(starting: &[u32], n: u32) -> Option<u32> {
+    nth_spoken_with::<FlatHistory>(starting, n)
+}
+
+/// Like [nth_spoken], remembering the numbers in a [History] of choice
+pub fn nth_spoken_with<H: History>(starting: &[u32], n: u32) -> Option<u32> {
+    if starting.is_empty() || n == 0 {
+        return None;
+    }
+
+    let mut history = H::with_turns(n);
+    let mut next = 0;
+    let mut spoken = 0;
+
+    for turn in 1..=n {
+        spoken = starting.get(turn as usize - 1).copied().unwrap_or(next);
+        next = history.replace(spoken, turn).map_or(0, |previous| turn - previous);
+    }
+    Some(spoken)
+}
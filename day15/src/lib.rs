@@ -0,0 +1,135 @@
+#![cfg_attr(feature = "unstable", feature(test))]
+
+pub use crate::game::{nth_spoken, nth_spoken_with, FlatHistory, HashHistory, History};
+
+mod game;
+
+const INPUT: &str = include_str!("../input");
+
+/// Panics if the starting numbers aren't comma separated integers
+pub fn parse(input: &str) -> Vec<u32> {
+    input.trim()
+        .split(',')
+        .map(|n| n.trim().parse().expect("failed to parse starting number"))
+        .collect()
+}
+
+fn solve_part_1(starting: &[u32]) -> u32 {
+    nth_spoken(starting, 2020).unwrap()
+}
+
+fn solve_part_2(starting: &[u32]) -> u32 {
+    nth_spoken(starting, 30_000_000).unwrap()
+}
+
+pub fn solve_input(input: &str) -> (u32, u32) {
+    let starting = &parse(input);
+    (solve_part_1(starting), solve_part_2(starting))
+}
+
+pub fn solve() -> (u32, u32) {
+    solve_input(INPUT)
+}
+
+// ============================================================================================== //
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const SAMPLE_01: &str = include_str!("../sample01");
+
+    #[test]
+    fn part_1_sample_input() {
+        assert_eq!(solve_part_1(&parse(SAMPLE_01)), 436);
+
+        let samples = [("1,3,2", 1), ("2,1,3", 10), ("1,2,3", 27), ("2,3,1", 78), ("3,2,1", 438), ("3,1,2", 1836)];
+        for &(starting, expected) in &samples {
+            assert_eq!(solve_part_1(&parse(starting)), expected, "starting {}", starting);
+        }
+    }
+
+    #[test]
+    fn part_1_puzzle_input() {
+        assert_eq!(solve_part_1(&parse(INPUT)), 260);
+    }
+
+    #[test]
+    fn part_2_sample_input() {
+        assert_eq!(solve_part_2(&parse(SAMPLE_01)), 175594);
+    }
+
+    #[test]
+    fn part_2_puzzle_input() {
+        assert_eq!(solve_part_2(&parse(INPUT)), 950);
+    }
+
+    #[test]
+    fn spoken_numbers() {
+        let spoken = (1..=10).map(|n| nth_spoken(&[0, 3, 6], n).unwrap()).collect::<Vec<_>>();
+        assert_eq!(spoken, vec![0, 3, 6, 0, 3, 3, 1, 0, 4, 0]);
+
+        assert_eq!(nth_spoken(&[], 5), None);
+        assert_eq!(nth_spoken(&[1, 2], 0), None);
+        // turns within the starting numbers, repeated starting numbers, and numbers beyond the
+        // number of turns
+        assert_eq!(nth_spoken(&[7, 8, 9], 2), Some(8));
+        assert_eq!(nth_spoken(&[1, 1], 3), Some(1));
+        assert_eq!(nth_spoken(&[1000, 1000, 5], 5), Some(0));
+        // starting numbers far past the number of turns don't size the history
+        assert_eq!(nth_spoken(&[4_000_000_000, 3, 4_000_000_000], 5), Some(0));
+        assert_eq!(nth_spoken(&[u32::MAX, 0], 4), Some(1));
+        for n in 1..50 {
+            let starting = [3_000_000_000, 1, 3_000_000_000, 2];
+            assert_eq!(nth_spoken(&starting, n), nth_spoken_with::<HashHistory>(&starting, n));
+        }
+    }
+
+    #[test]
+    fn histories_agree() {
+        for n in 1..200 {
+            assert_eq!(nth_spoken_with::<FlatHistory>(&[2, 0, 1], n), nth_spoken_with::<HashHistory>(&[2, 0, 1], n));
+        }
+        assert_eq!(nth_spoken_with::<HashHistory>(&parse(INPUT), 2020), Some(260));
+    }
+
+    #[cfg(all(feature = "unstable", test))]
+    mod bench {
+        extern crate test;
+
+        use crate::*;
+        use test::Bencher;
+
+        #[bench]
+        fn d15p1(b: &mut Bencher) {
+            let starting = &parse(INPUT);
+            b.iter(|| {
+                solve_part_1(starting)
+            });
+        }
+
+        #[bench]
+        fn d15p2(b: &mut Bencher) {
+            let starting = &parse(INPUT);
+            b.iter(|| {
+                solve_part_2(starting)
+            });
+        }
+
+        #[bench]
+        fn d15_flat_history(b: &mut Bencher) {
+            let starting = &parse(INPUT);
+            b.iter(|| {
+                nth_spoken_with::<FlatHistory>(starting, 1_000_000)
+            });
+        }
+
+        #[bench]
+        fn d15_hash_history(b: &mut Bencher) {
+            let starting = &parse(INPUT);
+            b.iter(|| {
+                nth_spoken_with::<HashHistory>(starting, 1_000_000)
+            });
+        }
+    }
+}
@@ -0,0 +1,146 @@
+#![cfg_attr(feature = "unstable", feature(test))]
+
+pub use crate::notes::{parse, Notes, NotesError, ParseError, Rule};
+pub use crate::solver::{assign, assign_fields, candidates, SolveError, MAX_FIELDS};
+
+mod notes;
+mod solver;
+
+const INPUT: &str = include_str!("../input");
+
+fn solve_part_1(notes: &Notes) -> u32 {
+    notes.error_rate()
+}
+
+/// The product of your ticket's `departure` fields
+fn solve_part_2(notes: &Notes) -> u64 {
+    let columns = assign_fields(notes).unwrap();
+    notes.rules.iter()
+        .zip(columns)
+        .filter(|(rule, _)| rule.name.starts_with("departure"))
+        .map(|(_, column)| notes.ticket[column] as u64)
+        .product()
+}
+
+pub fn solve_input(input: &str) -> (u32, u64) {
+    let notes = &parse(input).unwrap();
+    (solve_part_1(notes), solve_part_2(notes))
+}
+
+pub fn solve() -> (u32, u64) {
+    solve_input(INPUT)
+}
+
+// ============================================================================================== //
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const SAMPLE_01: &str = include_str!("../sample01");
+    const SAMPLE_02: &str = include_str!("../sample02");
+
+    #[test]
+    fn part_1_sample_input() {
+        assert_eq!(solve_part_1(&parse(SAMPLE_01).unwrap()), 71);
+    }
+
+    #[test]
+    fn part_1_puzzle_input() {
+        assert_eq!(solve_part_1(&parse(INPUT).unwrap()), 28536);
+    }
+
+    #[test]
+    fn part_2_sample_input() {
+        let notes = parse(SAMPLE_02).unwrap();
+        assert_eq!(assign_fields(&notes), Ok(vec![1, 0, 2]));
+        // no departure fields, so the empty product
+        assert_eq!(solve_part_2(&notes), 1);
+    }
+
+    #[test]
+    fn part_2_puzzle_input() {
+        assert_eq!(solve_part_2(&parse(INPUT).unwrap()), 104332189335966);
+    }
+
+    #[test]
+    fn parse_notes() {
+        let notes = parse(SAMPLE_01).unwrap();
+        assert_eq!(notes.rules[1], Rule { name: "row".to_owned(), ranges: vec![6..=11, 33..=44] });
+        assert_eq!(notes.rules[1].to_string(), "row: 6-11 or 33-44");
+        assert_eq!(notes.ticket, vec![7, 1, 14]);
+        assert_eq!(notes.nearby.len(), 4);
+        assert_eq!(notes.valid_tickets().collect::<Vec<_>>(), vec![&[7, 3, 47][..]]);
+
+        let errors = [
+            ("class: 1-3 or 5-7\n\nyour ticket:\n1,2\n", "line 4: expected 1 values, found 2"),
+            ("class: 3-1\n", "line 1: invalid range \"3-1\""),
+            ("class: 1-3\n\nnearby tickets:\n1\n", "line 3: expected \"your ticket:\""),
+            ("class: 1-3\n\nyour ticket:\n1\n\nnearby tickets:\n1\nx\n", "line 8: invalid value \"x\""),
+            ("class: 1-3\n\nyour ticket:\n1\n", "line 5: unexpected end of notes"),
+        ];
+        for &(notes, expected) in &errors {
+            assert_eq!(parse(notes).unwrap_err().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn propagation_and_search() {
+        // the puzzle's staircase, each field fits one more column than the last
+        assert_eq!(assign(&[0b0111, 0b0001, 0b1111, 0b0011]), Ok(vec![2, 0, 3, 1]));
+        // columns only one field fits
+        assert_eq!(assign(&[0b111, 0b011, 0b001]), Ok(vec![2, 1, 0]));
+        assert_eq!(assign(&[]), Ok(vec![]));
+
+        // the first two fields can swap columns
+        assert_eq!(assign(&[0b011, 0b011, 0b100]), Err(SolveError::Ambiguous(vec![0, 1])));
+        assert_eq!(assign(&[0b11, 0b11]), Err(SolveError::Ambiguous(vec![0, 1])));
+
+        // three fields fit the same two columns, only found out by searching
+        let candidates = [0b000011, 0b000011, 0b000011, 0b111111, 0b111111, 0b111111];
+        assert_eq!(assign(&candidates), Err(SolveError::NoAssignment));
+        assert_eq!(assign(&[0b01, 0b01]), Err(SolveError::NoAssignment));
+        assert_eq!(assign(&[0b11, 0b00]), Err(SolveError::NoAssignment));
+        // columns past the number of fields are ignored
+        assert_eq!(assign(&[0b101, 0b110]), Ok(vec![0, 1]));
+    }
+
+    #[test]
+    fn puzzle_candidates() {
+        let candidates = candidates(&parse(INPUT).unwrap()).unwrap();
+        let mut sizes = candidates.iter().map(|columns| columns.count_ones()).collect::<Vec<_>>();
+        sizes.sort_unstable();
+        assert_eq!(sizes, (1..=20).collect::<Vec<_>>());
+    }
+
+    #[cfg(all(feature = "unstable", test))]
+    mod bench {
+        extern crate test;
+
+        use crate::*;
+        use test::Bencher;
+
+        #[bench]
+        fn d16_parse(b: &mut Bencher) {
+            b.iter(|| {
+                parse(INPUT).unwrap()
+            });
+        }
+
+        #[bench]
+        fn d16p1(b: &mut Bencher) {
+            let notes = &parse(INPUT).unwrap();
+            b.iter(|| {
+                solve_part_1(notes)
+            });
+        }
+
+        #[bench]
+        fn d16p2(b: &mut Bencher) {
+            let notes = &parse(INPUT).unwrap();
+            b.iter(|| {
+                solve_part_2(notes)
+            });
+        }
+    }
+}
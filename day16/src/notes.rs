@@ -0,0 +1,199 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// A ticket field and the ranges its values fall in, `class: 1-3 or 5-7`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub name: String,
+    pub ranges: Vec<RangeInclusive<u32>>,
+}
+
+impl Rule {
+    pub fn contains(&self, value: u32) -> bool {
+        self.ranges.iter().any(|range| range.contains(&value))
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.name)?;
+        for (i, range) in self.ranges.iter().enumerate() {
+            let separator = if i == 0 { "" } else { " or " };
+            write!(f, "{}{}-{}", separator, range.start(), range.end())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Rule {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, ranges) = s.split_once(": ").ok_or_else(|| ParseError::InvalidRule(s.to_owned()))?;
+
+        let ranges = ranges.split(" or ")
+            .map(|range| {
+                let (start, end) = range.split_once('-').ok_or_else(|| ParseError::InvalidRange(range.to_owned()))?;
+                match (start.parse::<u32>(), end.parse::<u32>()) {
+                    (Ok(start), Ok(end)) if start <= end => Ok(start..=end),
+                    _ => Err(ParseError::InvalidRange(range.to_owned())),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Rule { name: name.to_owned(), ranges })
+    }
+}
+
+/// The rules, your ticket and the nearby tickets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notes {
+    pub rules: Vec<Rule>,
+    pub ticket: Vec<u32>,
+    pub nearby: Vec<Vec<u32>>,
+}
+
+impl Notes {
+    /// Values matching no rule at all
+    pub fn invalid_values<'a>(&'a self, ticket: &'a [u32]) -> impl Iterator<Item=u32> + 'a {
+        ticket.iter().copied().filter(move |&value| !self.rules.iter().any(|rule| rule.contains(value)))
+    }
+
+    /// Sum of every nearby ticket value matching no rule
+    pub fn error_rate(&self) -> u32 {
+        self.nearby.iter().flat_map(|ticket| self.invalid_values(ticket)).sum()
+    }
+
+    /// Nearby tickets where every value matches at least one rule
+    pub fn valid_tickets(&self) -> impl Iterator<Item=&[u32]> {
+        self.nearby.iter()
+            .map(Vec::as_slice)
+            .filter(move |ticket| self.invalid_values(ticket).next().is_none())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidRule(String),
+    /// Not `start-end` with `start <= end`
+    InvalidRange(String),
+    InvalidValue(String),
+    /// A ticket without a value for every rule
+    WrongLength { expected: usize, found: usize },
+    /// A section header like `your ticket:` is missing
+    ExpectedHeader(&'static str),
+    UnexpectedEnd,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidRule(s) => write!(f, "invalid rule {:?}, expected 'name: a-b or c-d'", s),
+            ParseError::InvalidRange(s) => write!(f, "invalid range {:?}", s),
+            ParseError::InvalidValue(s) => write!(f, "invalid value {:?}", s),
+            ParseError::WrongLength { expected, found } => write!(f, "expected {} values, found {}", expected, found),
+            ParseError::ExpectedHeader(header) => write!(f, "expected {:?}", header),
+            ParseError::UnexpectedEnd => f.write_str("unexpected end of notes"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// A [ParseError] in the notes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotesError {
+    /// 1-based line of the error
+    pub line: usize,
+    pub error: ParseError,
+}
+
+impl fmt::Display for NotesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl Error for NotesError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Parses the rules, then `your ticket:` and `nearby tickets:`, each section separated by a blank line
+pub fn parse(input: &str) -> Result<Notes, NotesError> {
+    let mut lines = Lines { lines: input.lines(), line: 0 };
+
+    let mut rules = Vec::new();
+    loop {
+        match lines.next()? {
+            "" => break,
+            rule => rules.push(rule.parse().map_err(|error| lines.error(error))?),
+        }
+    }
+
+    lines.expect("your ticket:")?;
+    let ticket = lines.ticket(rules.len())?;
+
+    lines.expect("nearby tickets:")?;
+    let mut nearby = Vec::new();
+    while let Some(line) = lines.try_next() {
+        if !line.is_empty() {
+            nearby.push(parse_ticket(line, rules.len()).map_err(|error| lines.error(error))?);
+        }
+    }
+
+    Ok(Notes { rules, ticket, nearby })
+}
+
+fn parse_ticket(s: &str, fields: usize) -> Result<Vec<u32>, ParseError> {
+    let values = s.split(',')
+        .map(|value| value.parse().map_err(|_| ParseError::InvalidValue(value.to_owned())))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if values.len() != fields {
+        return Err(ParseError::WrongLength { expected: fields, found: values.len() });
+    }
+    Ok(values)
+}
+
+/// The lines of the notes, keeping track of the line number for errors
+struct Lines<'a> {
+    lines: std::str::Lines<'a>,
+    /// 1-based line last returned
+    line: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn try_next(&mut self) -> Option<&'a str> {
+        self.line += 1;
+        self.lines.next().map(str::trim)
+    }
+
+    fn next(&mut self) -> Result<&'a str, NotesError> {
+        self.try_next().ok_or_else(|| self.error(ParseError::UnexpectedEnd))
+    }
+
+    /// Skips blank lines up to [header]
+    fn expect(&mut self, header: &'static str) -> Result<(), NotesError> {
+        let mut line = self.next()?;
+        while line.is_empty() {
+            line = self.next()?;
+        }
+        if line != header {
+            return Err(self.error(ParseError::ExpectedHeader(header)));
+        }
+        Ok(())
+    }
+
+    fn ticket(&mut self, fields: usize) -> Result<Vec<u32>, NotesError> {
+        let line = self.next()?;
+        parse_ticket(line, fields).map_err(|error| self.error(error))
+    }
+
+    fn error(&self, error: ParseError) -> NotesError {
+        NotesError { line: self.line, error }
+    }
+}
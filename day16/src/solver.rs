@@ -0,0 +1,161 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::notes::Notes;
+
+/// Sets of columns are bitsets, which caps the number of fields
+pub const MAX_FIELDS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// More fields than [MAX_FIELDS]
+    TooManyFields(usize),
+    /// The fields can't each be given their own column
+    NoAssignment,
+    /// The fields, by index, that fit more than one column in different assignments
+    Ambiguous(Vec<usize>),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::TooManyFields(n) => write!(f, "{} fields, at most {} are supported", n, MAX_FIELDS),
+            SolveError::NoAssignment => f.write_str("the fields can't each be given their own column"),
+            SolveError::Ambiguous(fields) => write!(f, "fields {:?} fit more than one column", fields),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+/// The columns each rule could describe, as a bitset per rule. A column fits a rule when every
+/// value in it on the valid nearby tickets matches the rule.
+pub fn candidates(notes: &Notes) -> Result<Vec<u64>, SolveError> {
+    let fields = notes.rules.len();
+    if fields > MAX_FIELDS {
+        return Err(SolveError::TooManyFields(fields));
+    }
+
+    let tickets = notes.valid_tickets().collect::<Vec<_>>();
+    let candidates = notes.rules.iter()
+        .map(|rule| {
+            (0..fields)
+                .filter(|&column| tickets.iter().all(|ticket| rule.contains(ticket[column])))
+                .fold(0, |columns, column| columns | 1 << column)
+        })
+        .collect();
+    Ok(candidates)
+}
+
+/// The column of every field, given the [candidates] of every field.
+///
+/// Constraint propagation settles fields with a single possible column, and columns that only fit
+/// a single field. When that stalls the field with the fewest candidates is tried in each of its
+/// columns in turn, looking for a second assignment to tell a unique one from an ambiguous one.
+pub fn assign(candidates: &[u64]) -> Result<Vec<usize>, SolveError> {
+    if candidates.len() > MAX_FIELDS {
+        return Err(SolveError::TooManyFields(candidates.len()));
+    }
+
+    // there are as many columns as fields
+    let all = all_columns(candidates.len());
+    let mut solutions = Vec::new();
+    search(candidates.iter().map(|columns| columns & all).collect(), &mut solutions);
+
+    match solutions.as_slice() {
+        [] => Err(SolveError::NoAssignment),
+        [solution] => Ok(solution.iter().map(|columns| columns.trailing_zeros() as usize).collect()),
+        [first, second, ..] => {
+            let fields = (0..candidates.len()).filter(|&i| first[i] != second[i]).collect();
+            Err(SolveError::Ambiguous(fields))
+        }
+    }
+}
+
+/// [candidates] and [assign]
+pub fn assign_fields(notes: &Notes) -> Result<Vec<usize>, SolveError> {
+    assign(&candidates(notes)?)
+}
+
+/// A bitset of the first [n] columns
+fn all_columns(n: usize) -> u64 {
+    match n {
+        0 => 0,
+        n => u64::MAX >> (64 - n),
+    }
+}
+
+/// Collects up to two solutions reachable from [candidates]
+fn search(mut candidates: Vec<u64>, solutions: &mut Vec<Vec<u64>>) {
+    if !propagate(&mut candidates) {
+        return;
+    }
+
+    let undecided = candidates.iter()
+        .enumerate()
+        .filter(|(_, columns)| columns.count_ones() > 1)
+        .min_by_key(|(_, columns)| columns.count_ones());
+
+    let (field, &columns) = match undecided {
+        Some(undecided) => undecided,
+        None => return solutions.push(candidates),
+    };
+
+    let mut remaining = columns;
+    while remaining != 0 && solutions.len() < 2 {
+        let column = remaining & remaining.wrapping_neg();
+        remaining &= !column;
+
+        let mut guess = candidates.clone();
+        guess[field] = column;
+        search(guess, solutions);
+    }
+}
+
+/// Narrows down [candidates] until nothing changes, returns false if some field or column is left
+/// without a match
+fn propagate(candidates: &mut [u64]) -> bool {
+    let all = all_columns(candidates.len());
+
+    loop {
+        let mut changed = false;
+
+        // a field with a single column takes it from every other field
+        for field in 0..candidates.len() {
+            let columns = candidates[field];
+            if columns == 0 {
+                return false;
+            }
+            if columns.count_ones() != 1 {
+                continue;
+            }
+            for (other, others) in candidates.iter_mut().enumerate() {
+                if other != field && *others & columns != 0 {
+                    *others &= !columns;
+                    changed = true;
+                }
+            }
+        }
+
+        // a column only a single field fits goes to that field
+        let mut remaining = all;
+        while remaining != 0 {
+            let column = remaining & remaining.wrapping_neg();
+            remaining &= !column;
+
+            let mut fields = candidates.iter_mut().filter(|columns| **columns & column != 0);
+            match (fields.next(), fields.next()) {
+                (None, _) => return false,
+                (Some(columns), None) if *columns != column => {
+                    *columns = column;
+                    changed = true;
+                }
+                _ => {}
+            }
+        }
+
+        if !changed {
+            return true;
+        }
+    }
+}
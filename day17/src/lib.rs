@@ -0,0 +1,162 @@
+#![cfg_attr(feature = "unstable", feature(test))]
+
+use grid::Grid;
+
+pub use crate::pocket::{Pocket, Point, ACTIVE, INACTIVE};
+
+mod pocket;
+
+const INPUT: &str = include_str!("../input");
+
+/// The boot process runs for six cycles
+pub const CYCLES: usize = 6;
+
+pub fn parse(input: &str) -> Grid {
+    Grid::parse(input).expect("failed to parse starting slice")
+}
+
+fn solve_part_1(slice: &Grid) -> usize {
+    Pocket::<3>::new(slice).run(CYCLES)
+}
+
+fn solve_part_2(slice: &Grid) -> usize {
+    Pocket::<4>::new(slice).run(CYCLES)
+}
+
+pub fn solve_input(input: &str) -> (usize, usize) {
+    let slice = &parse(input);
+    (solve_part_1(slice), solve_part_2(slice))
+}
+
+pub fn solve() -> (usize, usize) {
+    solve_input(INPUT)
+}
+
+// ============================================================================================== //
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const SAMPLE_01: &str = include_str!("../sample01");
+
+    #[test]
+    fn part_1_sample_input() {
+        assert_eq!(solve_part_1(&parse(SAMPLE_01)), 112);
+    }
+
+    #[test]
+    fn part_1_puzzle_input() {
+        assert_eq!(solve_part_1(&parse(INPUT)), 207);
+    }
+
+    #[test]
+    fn part_2_sample_input() {
+        assert_eq!(solve_part_2(&parse(SAMPLE_01)), 848);
+    }
+
+    #[test]
+    fn part_2_puzzle_input() {
+        assert_eq!(solve_part_2(&parse(INPUT)), 1900);
+    }
+
+    #[test]
+    fn symmetry_matches_full_simulation() {
+        let slice = &parse(SAMPLE_01);
+
+        let mut symmetric = Pocket::<4>::new(slice);
+        let mut full = Pocket::<4>::without_symmetry(slice);
+        for _ in 0..CYCLES {
+            symmetric.step();
+            full.step();
+            let (mut a, mut b) = (symmetric.cubes(), full.cubes());
+            a.sort_unstable();
+            b.sort_unstable();
+            assert_eq!(a, b, "cycle {}", symmetric.cycle());
+        }
+
+        assert_eq!(Pocket::<5>::new(slice).run(3), 2288);
+        assert_eq!(Pocket::<5>::without_symmetry(&parse(INPUT)).run(3), 6356);
+        assert_eq!(Pocket::<5>::new(&parse(INPUT)).run(3), 6356);
+    }
+
+    #[test]
+    fn other_dimensions() {
+        // two dimensions is the game of life, where a glider keeps its five cells
+        let glider = &parse(".#.\n..#\n###");
+        assert_eq!(Pocket::<2>::new(glider).run(8), 5);
+
+        // the higher the dimension the faster it grows
+        let counts = [
+            Pocket::<3>::new(glider).run(2),
+            Pocket::<4>::new(glider).run(2),
+            Pocket::<5>::new(glider).run(2),
+            Pocket::<6>::new(glider).run(2),
+        ];
+        assert!(counts.windows(2).all(|w| w[0] < w[1]), "{:?}", counts);
+    }
+
+    #[test]
+    fn slice_output() {
+        let mut pocket = Pocket::<3>::new(&parse(SAMPLE_01));
+        assert_eq!(pocket.slices(), "z=0\n.#.\n..#\n###\n");
+
+        pocket.step();
+        assert_eq!(pocket.slices(), "\
+z=-1
+#..
+..#
+.#.
+
+z=0
+#.#
+.##
+.#.
+
+z=1
+#..
+..#
+.#.
+");
+
+        let mut pocket = Pocket::<4>::new(&parse(SAMPLE_01));
+        pocket.step();
+        let labels = pocket.slices().lines().filter(|line| line.starts_with('z')).map(str::to_owned).collect::<Vec<_>>();
+        assert_eq!(labels.len(), 9);
+        assert_eq!(labels[0], "z=-1, w=-1");
+        assert_eq!(labels[1], "z=0, w=-1");
+        assert_eq!(labels[8], "z=1, w=1");
+    }
+
+    #[cfg(all(feature = "unstable", test))]
+    mod bench {
+        extern crate test;
+
+        use crate::*;
+        use test::Bencher;
+
+        #[bench]
+        fn d17p1(b: &mut Bencher) {
+            let slice = &parse(INPUT);
+            b.iter(|| {
+                solve_part_1(slice)
+            });
+        }
+
+        #[bench]
+        fn d17p2(b: &mut Bencher) {
+            let slice = &parse(INPUT);
+            b.iter(|| {
+                solve_part_2(slice)
+            });
+        }
+
+        #[bench]
+        fn d17p2_without_symmetry(b: &mut Bencher) {
+            let slice = &parse(INPUT);
+            b.iter(|| {
+                Pocket::<4>::without_symmetry(slice).run(CYCLES)
+            });
+        }
+    }
+}
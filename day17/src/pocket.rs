@@ -0,0 +1,253 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use grid::Grid;
+
+pub const ACTIVE: u8 = b'#';
+pub const INACTIVE: u8 = b'.';
+
+/// A cube's coordinates, `x` and `y` first then the extra dimensions
+pub type Point<const D: usize> = [i32; D];
+
+/// Conway cubes in [D] dimensions, starting from a single 2D slice.
+///
+/// Only active cubes are stored. The starting slice sits at 0 in every extra dimension and the
+/// rules treat every dimension alike, so the pocket stays symmetric under negating any extra
+/// coordinate and under swapping any two of them. With symmetry on only one cube per group of
+/// symmetric cubes is kept, its canonical form with the extra coordinates made positive and
+/// sorted, which shrinks the work by about `2^(D-2)·(D-2)!` in 4 or more dimensions.
+#[derive(Debug, Clone)]
+pub struct Pocket<const D: usize> {
+    /// Every active cube, or only the canonical ones with [symmetric]
+    active: HashSet<Point<D>>,
+    symmetric: bool,
+    cycle: usize,
+    /// Every offset to a neighboring cube
+    offsets: Vec<Point<D>>,
+}
+
+impl<const D: usize> Pocket<D> {
+    /// A pocket making use of symmetry, panics unless [D] is at least 2
+    pub fn new(slice: &Grid) -> Pocket<D> {
+        Pocket::with_symmetry(slice, true)
+    }
+
+    /// A pocket simulating every active cube
+    pub fn without_symmetry(slice: &Grid) -> Pocket<D> {
+        Pocket::with_symmetry(slice, false)
+    }
+
+    fn with_symmetry(slice: &Grid, symmetric: bool) -> Pocket<D> {
+        assert!(D >= 2, "the starting slice needs at least 2 dimensions, got {}", D);
+
+        let active = (0..slice.cells().len())
+            .filter(|&i| slice.cells()[i] == ACTIVE)
+            .map(|i| {
+                let (x, y) = slice.position(i);
+                let mut point = [0; D];
+                point[0] = x as i32;
+                point[1] = y as i32;
+                point
+            })
+            .collect();
+
+        let offsets = (0..3usize.pow(D as u32))
+            .map(|mut n| {
+                let mut offset = [0; D];
+                for coordinate in offset.iter_mut() {
+                    *coordinate = (n % 3) as i32 - 1;
+                    n /= 3;
+                }
+                offset
+            })
+            .filter(|offset| offset.iter().any(|&c| c != 0))
+            .collect();
+
+        Pocket { active, symmetric, cycle: 0, offsets }
+    }
+
+    /// Number of cycles run so far
+    pub fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    /// Number of active cubes
+    pub fn active(&self) -> usize {
+        if self.symmetric {
+            self.active.iter().map(|point| orbit_size(&point[2..])).sum()
+        } else {
+            self.active.len()
+        }
+    }
+
+    /// Every active cube, in no particular order
+    pub fn cubes(&self) -> Vec<Point<D>> {
+        if self.symmetric {
+            self.active.iter().flat_map(orbit).collect()
+        } else {
+            self.active.iter().copied().collect()
+        }
+    }
+
+    /// Runs one cycle: an active cube stays active with 2 or 3 active neighbors, an inactive cube
+    /// becomes active with exactly 3.
+    pub fn step(&mut self) {
+        let neighbors = if self.symmetric { self.count_symmetric() } else { self.count() };
+
+        self.active = neighbors.into_iter()
+            .filter(|(point, count)| *count == 3 || *count == 2 && self.active.contains(point))
+            .map(|(point, _)| point)
+            .collect();
+        self.cycle += 1;
+    }
+
+    /// Runs [cycles] cycles, returns the number of active cubes
+    pub fn run(&mut self, cycles: usize) -> usize {
+        for _ in 0..cycles {
+            self.step();
+        }
+        self.active()
+    }
+
+    /// The number of active neighbors of every cube with any
+    fn count(&self) -> HashMap<Point<D>, usize> {
+        let mut counts = HashMap::new();
+        for point in &self.active {
+            for offset in &self.offsets {
+                *counts.entry(add(point, offset)).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// [count] for canonical cubes.
+    ///
+    /// Each canonical cube `a` only reaches its own neighbors, not those of the other cubes `a'`
+    /// in its orbit. By symmetry the number of adjacent pairs between the orbits of `a` and of a
+    /// neighbor `n` is both `|orbit(a)|·hits(a, n)` and `|orbit(n)|·count(n)`, where `hits(a, n)`
+    /// is how many of the neighbors of `a` are in the orbit of `n`, so `count(n)` comes from
+    /// weighting each hit by `|orbit(a)|` and dividing by `|orbit(n)|` at the end.
+    fn count_symmetric(&self) -> HashMap<Point<D>, usize> {
+        let mut weights = HashMap::new();
+        for point in &self.active {
+            let weight = orbit_size(&point[2..]);
+            for offset in &self.offsets {
+                *weights.entry(canonical(add(point, offset))).or_insert(0) += weight;
+            }
+        }
+
+        weights.into_iter()
+            .map(|(point, weight)| (point, weight / orbit_size(&point[2..])))
+            .collect()
+    }
+
+    /// Every `x`/`y` slice holding an active cube, within the bounds of all active cubes.
+    ///
+    /// Each slice is labeled with its extra coordinates, as in `z=0, w=1`, followed by the cubes
+    /// with `#` for active and `.` for inactive. Slices are separated by a blank line.
+    pub fn slices(&self) -> String {
+        let cubes = self.cubes();
+        let mut out = String::new();
+        if cubes.is_empty() {
+            return out;
+        }
+
+        let min = (0..D).map(|d| cubes.iter().map(|p| p[d]).min().unwrap()).collect::<Vec<_>>();
+        let max = (0..D).map(|d| cubes.iter().map(|p| p[d]).max().unwrap()).collect::<Vec<_>>();
+
+        let mut slices: HashMap<&[i32], Grid> = HashMap::new();
+        for cube in &cubes {
+            let slice = slices.entry(&cube[2..]).or_insert_with(|| {
+                Grid::new((max[0] - min[0] + 1) as usize, (max[1] - min[1] + 1) as usize, INACTIVE)
+            });
+            slice.set((cube[0] - min[0]) as usize, (cube[1] - min[1]) as usize, ACTIVE);
+        }
+
+        let mut slices = slices.into_iter().collect::<Vec<_>>();
+        slices.sort_unstable_by(|(a, _), (b, _)| a.iter().rev().cmp(b.iter().rev()));
+
+        for (i, (coordinates, slice)) in slices.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            let label = coordinates.iter()
+                .enumerate()
+                .map(|(d, c)| format!("{}={}", axis(d + 2), c))
+                .collect::<Vec<_>>()
+                .join(", ");
+            if !label.is_empty() {
+                writeln!(out, "{}", label).unwrap();
+            }
+            writeln!(out, "{}", slice).unwrap();
+        }
+        out
+    }
+}
+
+/// `x`, `y`, `z` and `w`, then `d5`, `d6`... by 1-based dimension
+fn axis(dimension: usize) -> String {
+    match dimension {
+        0..=3 => ["x", "y", "z", "w"][dimension].to_owned(),
+        d => format!("d{}", d + 1),
+    }
+}
+
+fn add<const D: usize>(a: &Point<D>, b: &Point<D>) -> Point<D> {
+    let mut sum = *a;
+    for (c, b) in sum.iter_mut().zip(b) {
+        *c += b;
+    }
+    sum
+}
+
+/// The representative of a cube's orbit, extra coordinates made positive and sorted
+fn canonical<const D: usize>(mut point: Point<D>) -> Point<D> {
+    for c in &mut point[2..] {
+        *c = c.abs();
+    }
+    point[2..].sort_unstable();
+    point
+}
+
+/// How many cubes share the canonical [extra] coordinates: `2^nonzero` sign flips times the
+/// distinct orderings, `k! / (m1!·m2!·...)` for `k` coordinates with repeats `m1`, `m2`...
+fn orbit_size(extra: &[i32]) -> usize {
+    let factorial = |n: usize| (1..=n).product::<usize>();
+    let signs = 1 << extra.iter().filter(|&&c| c != 0).count();
+
+    let mut orderings = factorial(extra.len());
+    let mut i = 0;
+    while i < extra.len() {
+        let repeats = extra[i..].iter().take_while(|&&c| c == extra[i]).count();
+        orderings /= factorial(repeats);
+        i += repeats;
+    }
+    signs * orderings
+}
+
+/// Every cube symmetric to a canonical one
+fn orbit<const D: usize>(point: &Point<D>) -> HashSet<Point<D>> {
+    let mut points = HashSet::new();
+    points.insert(*point);
+
+    // swapping neighboring extra coordinates reaches every ordering, and negating each reaches
+    // every sign, so apply both until nothing new turns up
+    let k = D - 2;
+    loop {
+        let mut next = points.clone();
+        for p in &points {
+            for i in 2..D {
+                let mut swapped = *p;
+                swapped.swap(i, 2 + (i - 1) % k);
+                next.insert(swapped);
+                let mut negated = *p;
+                negated[i] = -negated[i];
+                next.insert(negated);
+            }
+        }
+        if next.len() == points.len() {
+            return points;
+        }
+        points = next;
+    }
+}
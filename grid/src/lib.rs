@@ -0,0 +1,150 @@
+//! Rectangular grids of ascii characters, as used by the map and seat layout inputs. Cells are
+//! stored row by row in a single buffer and addressed by `(x, y)`, with `(0, 0)` the top left.
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid {
+    width: usize,
+    height: usize,
+    cells: Vec<u8>,
+}
+
+impl Grid {
+    /// A [width] by [height] grid with every cell set to [fill]
+    pub fn new(width: usize, height: usize, fill: u8) -> Grid {
+        Grid { width, height, cells: vec![fill; width * height] }
+    }
+
+    /// One row per line, every line must be the same length. A trailing `\r` is ignored.
+    pub fn parse(input: &str) -> Result<Grid, GridError> {
+        let mut grid = Grid { width: 0, height: 0, cells: vec![] };
+
+        for (i, line) in input.lines().enumerate() {
+            let row = line.trim_end_matches('\r').as_bytes();
+            if i == 0 {
+                grid.width = row.len();
+            } else if row.len() != grid.width {
+                return Err(GridError { line: i + 1, expected: grid.width, found: row.len() });
+            }
+            grid.cells.extend_from_slice(row);
+            grid.height += 1;
+        }
+
+        Ok(grid)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Index of `(x, y)` in [cells]
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    /// `(x, y)` of [index] in [cells]
+    pub fn position(&self, index: usize) -> (usize, usize) {
+        (index % self.width, index / self.width)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        self.cells[self.index(x, y)]
+    }
+
+    /// The cell at `(x, y)`, `None` if it's outside the grid. Takes signed coordinates so callers
+    /// can step off any edge.
+    pub fn try_get(&self, x: isize, y: isize) -> Option<u8> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            None
+        } else {
+            Some(self.get(x as usize, y as usize))
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, cell: u8) {
+        let i = self.index(x, y);
+        self.cells[i] = cell;
+    }
+
+    pub fn row(&self, y: usize) -> &[u8] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Every cell, row by row
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut [u8] {
+        &mut self.cells
+    }
+}
+
+/// Rows separated by `\n`, without a trailing `\n`
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..self.height {
+            if y > 0 {
+                f.write_str("\n")?;
+            }
+            f.write_str(&String::from_utf8_lossy(self.row(y)))?;
+        }
+        Ok(())
+    }
+}
+
+/// A line whose length differs from the first line's
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridError {
+    /// 1-based line of the input
+    pub line: usize,
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: expected {} cells, found {}", self.line, self.expected, self.found)
+    }
+}
+
+impl Error for GridError {}
+
+// ============================================================================================== //
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn parse_and_display() {
+        let grid = Grid::parse("#..\r\n.#.\n").unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(1, 1), b'#');
+        assert_eq!(grid.row(0), b"#..");
+        assert_eq!(grid.position(grid.index(2, 1)), (2, 1));
+        assert_eq!(grid.to_string(), "#..\n.#.");
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let grid = Grid::new(2, 2, b'.');
+
+        assert_eq!(grid.try_get(1, 1), Some(b'.'));
+        assert_eq!(grid.try_get(-1, 0), None);
+        assert_eq!(grid.try_get(0, 2), None);
+    }
+
+    #[test]
+    fn ragged_rows() {
+        assert_eq!(Grid::parse("..\n...").unwrap_err(), GridError { line: 2, expected: 2, found: 3 });
+        assert_eq!(Grid::parse("").unwrap(), Grid::new(0, 0, b'.'));
+    }
+}
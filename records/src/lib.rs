@@ -0,0 +1,136 @@
+//! Streams blank line separated records, as used by the passport and customs declaration inputs,
+//! from any [BufRead]. Only the record being read is held in memory, so inputs of any size can be
+//! processed in constant memory.
+
+use std::io::{self, BufRead};
+
+/// The lines of a single record, without line terminators
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    text: String,
+    first_line: usize,
+    last_line: usize,
+}
+
+impl Record {
+    /// Every line of the record joined with `\n`, without a trailing `\n`
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item=&str> {
+        self.text.split('\n')
+    }
+
+    /// 1-based line of the input this record starts on
+    pub fn first_line(&self) -> usize {
+        self.first_line
+    }
+
+    /// 1-based line of the input this record ends on
+    pub fn last_line(&self) -> usize {
+        self.last_line
+    }
+}
+
+/// Iterator over the records of a reader.
+///
+/// Records are separated by one or more blank lines, a line containing only whitespace counts as
+/// blank. Both `\n` and `\r\n` line endings are accepted.
+pub struct Records<R> {
+    reader: R,
+    line: String,
+    line_number: usize,
+}
+
+impl<R: BufRead> Records<R> {
+    pub fn new(reader: R) -> Records<R> {
+        Records { reader, line: String::new(), line_number: 0 }
+    }
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record: Option<Record> = None;
+
+        loop {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return record.map(Ok),
+                Ok(_) => self.line_number += 1,
+                Err(e) => return Some(Err(e)),
+            }
+
+            let line = self.line.trim_end_matches(&['\n', '\r'][..]);
+            if line.trim().is_empty() {
+                if record.is_some() {
+                    return record.map(Ok);
+                }
+                continue;
+            }
+
+            match &mut record {
+                Some(record) => {
+                    record.text.push('\n');
+                    record.text.push_str(line);
+                    record.last_line = self.line_number;
+                }
+                None => {
+                    record = Some(Record {
+                        text: line.to_owned(),
+                        first_line: self.line_number,
+                        last_line: self.line_number,
+                    })
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================================== //
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn records(input: &str) -> Vec<Record> {
+        Records::new(input.as_bytes()).collect::<io::Result<Vec<_>>>().unwrap()
+    }
+
+    #[test]
+    fn blank_line_separated() {
+        let records = records("a b\nc\n\nd\n");
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].text(), "a b\nc");
+        assert_eq!(records[0].lines().collect::<Vec<_>>(), vec!["a b", "c"]);
+        assert_eq!((records[0].first_line(), records[0].last_line()), (1, 2));
+        assert_eq!(records[1].text(), "d");
+        assert_eq!((records[1].first_line(), records[1].last_line()), (4, 4));
+    }
+
+    #[test]
+    fn crlf_and_blank_runs() {
+        let records = records("\r\n\r\na\r\nb\r\n\r\n  \r\n\t\r\nc");
+
+        assert_eq!(records.iter().map(Record::text).collect::<Vec<_>>(), vec!["a\nb", "c"]);
+        assert_eq!((records[0].first_line(), records[0].last_line()), (3, 4));
+        assert_eq!((records[1].first_line(), records[1].last_line()), (8, 8));
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(records(""), vec![]);
+        assert_eq!(records("\n\n \n"), vec![]);
+    }
+
+    #[test]
+    fn io_errors() {
+        let mut records = Records::new(&b"a\n\n\xff\n"[..]);
+
+        assert_eq!(records.next().unwrap().unwrap().text(), "a");
+        assert_eq!(records.next().unwrap().unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,208 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::days::Answers;
+use crate::runner::Outcome;
+
+/// The answers file next to an input: `inputs/day04/alice.txt` is checked against
+/// `inputs/day04/alice.answers`, holding part 1 on its first line and part 2 on its second
+pub fn answers_path(input: &Path) -> PathBuf {
+    input.with_extension("answers")
+}
+
+/// Whether [path] is an answers file rather than a puzzle input
+pub fn is_answers(path: &Path) -> bool {
+    path == answers_path(path)
+}
+
+/// The puzzle inputs among [files], dropping answers files so that a glob over a directory holding
+/// both can be passed as is
+pub fn inputs(files: Vec<PathBuf>) -> Vec<PathBuf> {
+    files.into_iter().filter(|path| !is_answers(path)).collect()
+}
+
+/// The expected answers for [input], `None` if it has no answers file
+pub fn expected(input: &Path) -> Option<Result<Answers, String>> {
+    let path = answers_path(input);
+    if !path.is_file() {
+        return None;
+    }
+
+    let answers = fs::read_to_string(&path)
+        .map_err(|e| format!("{}: {}", path.display(), e))
+        .and_then(|text| {
+            let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+            match (lines.next(), lines.next()) {
+                (Some(part1), Some(part2)) => Ok((part1.to_owned(), part2.to_owned())),
+                _ => Err(format!("{}: expected one answer per line for both parts", path.display())),
+            }
+        });
+    Some(answers)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    /// No answers file to check against
+    Unchecked,
+    Correct,
+    Mismatch,
+    Error(String),
+}
+
+/// One row of the batch table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub file: String,
+    pub part1: String,
+    pub part2: String,
+    pub elapsed: Duration,
+    pub status: Status,
+}
+
+impl Row {
+    pub fn new(outcome: &Outcome, expected: Option<Result<Answers, String>>) -> Row {
+        let file = outcome.task.input.to_string();
+        let elapsed = outcome.elapsed;
+
+        let (part1, part2) = match &outcome.answers {
+            Ok(answers) => answers.clone(),
+            Err(e) => {
+                let status = Status::Error(e.clone());
+                return Row { file, part1: "-".to_owned(), part2: "-".to_owned(), elapsed, status };
+            }
+        };
+
+        let (part1, part2, status) = match expected {
+            None => (part1, part2, Status::Unchecked),
+            Some(Err(e)) => (part1, part2, Status::Error(e)),
+            Some(Ok((expected1, expected2))) => {
+                let mismatch = part1 != expected1 || part2 != expected2;
+                (
+                    mark(part1, &expected1),
+                    mark(part2, &expected2),
+                    if mismatch { Status::Mismatch } else { Status::Correct },
+                )
+            }
+        };
+
+        Row { file, part1, part2, elapsed, status }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(self.status, Status::Mismatch | Status::Error(_))
+    }
+}
+
+/// [actual], followed by what was expected when they differ
+fn mark(actual: String, expected: &str) -> String {
+    if actual == expected { actual } else { format!("{} (expected {})", actual, expected) }
+}
+
+/// Renders [rows] as an aligned table, with any errors listed below it
+pub fn table(rows: &[Row]) -> String {
+    let header = ["file", "part 1", "part 2", "time", "status"];
+    let cells = rows.iter()
+        .map(|row| [
+            row.file.clone(),
+            row.part1.clone(),
+            row.part2.clone(),
+            format!("{:.3}ms", row.elapsed.as_secs_f64() * 1000.0),
+            match &row.status {
+                Status::Unchecked => "-",
+                Status::Correct => "ok",
+                Status::Mismatch => "MISMATCH",
+                Status::Error(_) => "ERROR",
+            }.to_owned(),
+        ])
+        .collect::<Vec<_>>();
+
+    let mut widths = header.map(str::len);
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let mut write_row = |row: &[String]| {
+        let line = row.iter().zip(&widths)
+            .map(|(cell, &width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    };
+
+    write_row(&header.map(str::to_owned));
+    write_row(&widths.map(|width| "-".repeat(width)));
+    for row in &cells {
+        write_row(row);
+    }
+
+    for row in rows {
+        if let Status::Error(e) = &row.status {
+            writeln!(out, "\n{}: {}", row.file, e).unwrap();
+        }
+    }
+
+    out
+}
+
+// ============================================================================================== //
+
+#[cfg(test)]
+mod tests {
+    use crate::batch::*;
+
+    fn row(part1: &str, part2: &str, status: Status) -> Row {
+        Row {
+            file: "inputs/day04/alice.txt".to_owned(),
+            part1: part1.to_owned(),
+            part2: part2.to_owned(),
+            elapsed: Duration::from_micros(1500),
+            status,
+        }
+    }
+
+    #[test]
+    fn answers_path_replaces_extension() {
+        assert_eq!(answers_path(Path::new("inputs/day04/alice.txt")), Path::new("inputs/day04/alice.answers"));
+        assert_eq!(answers_path(Path::new("bob")), Path::new("bob.answers"));
+    }
+
+    #[test]
+    fn inputs_skip_answers_files() {
+        // as the shell expands `inputs/day05/*`
+        let glob = ["inputs/day05/a.answers", "inputs/day05/a.txt", "inputs/day05/b", "inputs/day05/b.answers"];
+        let files = glob.iter().map(PathBuf::from).collect();
+        assert_eq!(inputs(files), vec![PathBuf::from("inputs/day05/a.txt"), PathBuf::from("inputs/day05/b")]);
+        assert!(inputs(vec![PathBuf::from("c.answers")]).is_empty());
+    }
+
+    #[test]
+    fn marks_mismatches() {
+        assert_eq!(mark("2".to_owned(), "2"), "2");
+        assert_eq!(mark("2".to_owned(), "3"), "2 (expected 3)");
+    }
+
+    #[test]
+    fn aligned_table() {
+        let rows = vec![
+            row("237", "172 (expected 171)", Status::Mismatch),
+            row("2", "2", Status::Correct),
+            row("-", "-", Status::Error("solver panicked: oops".to_owned())),
+        ];
+
+        assert_eq!(table(&rows), "\
+file                    part 1  part 2              time     status
+----------------------  ------  ------------------  -------  --------
+inputs/day04/alice.txt  237     172 (expected 171)  1.500ms  MISMATCH
+inputs/day04/alice.txt  2       2                   1.500ms  ok
+inputs/day04/alice.txt  -       -                   1.500ms  ERROR
+
+inputs/day04/alice.txt: solver panicked: oops
+");
+        assert!(rows[0].is_failure() && !rows[1].is_failure() && rows[2].is_failure());
+    }
+}
@@ -0,0 +1,49 @@
+use std::fmt::Display;
+
+/// Both answers of a day, formatted for display
+pub type Answers = (String, String);
+
+/// A day's solver, either on its bundled puzzle input or on input supplied at runtime
+pub struct Day {
+    pub day: usize,
+    pub solve: fn() -> Answers,
+    pub solve_input: fn(&str) -> Answers,
+}
+
+fn answers<A: Display, B: Display>((part1, part2): (A, B)) -> Answers {
+    (part1.to_string(), part2.to_string())
+}
+
+macro_rules! day {
+    ($day:expr, $krate:ident) => {
+        Day {
+            day: $day,
+            solve: || answers($krate::solve()),
+            solve_input: |input| answers($krate::solve_input(input)),
+        }
+    };
+}
+
+pub const DAYS: &[Day] = &[
+    day!(1, day01),
+    day!(2, day02),
+    day!(3, day03),
+    day!(4, day04),
+    day!(5, day05),
+    day!(6, day06),
+    day!(7, day07),
+    day!(8, day08),
+    day!(9, day09),
+    day!(10, day10),
+    day!(11, day11),
+    day!(12, day12),
+    day!(13, day13),
+    day!(14, day14),
+    day!(15, day15),
+    day!(16, day16),
+    day!(17, day17),
+];
+
+pub fn find(day: usize) -> Option<&'static Day> {
+    DAYS.iter().find(|it| it.day == day)
+}
@@ -1,36 +1,221 @@
 #![cfg_attr(feature = "unstable", feature(test))]
 
-use day01;
-use day02;
-use day03;
-use day04;
-use day05;
-use day06;
-use std::fmt::Display;
-
-fn solve_day<A, B, S>(day: usize, solver: S) where
-    A: Display,
-    B: Display,
-    S: Fn() -> (A, B)
-{
-    let (part1, part2) = solver();
-    println!("Day {:02}\n    Part 1: {}\n    Part 2: {}", day, part1, part2);
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::batch::Row;
+use crate::days::{Day, DAYS};
+use crate::runner::{Input, Task};
+
+mod batch;
+mod days;
+mod runner;
+mod watch;
+
+const USAGE: &str = "\
+usage: aoc2020                 solve every day on its bundled input
+       aoc2020 run [options] [DAY...]
+       aoc2020 batch --day DAY [--jobs N] FILE...
+       aoc2020 watch DAY [--interval MS]
+
+options:
+    --jobs N        solve on N threads, defaults to the number of cores
+    --inputs DIR    also solve every file in DIR/dayNN/, defaults to inputs
+    --day DAY       the day whose solver is run on every FILE
+    --interval MS   how often watch polls the day's files, defaults to 500
+
+A FILE with a sibling .answers file, holding the answer to part 1 on its first line and part 2
+on its second, is checked against it. Answers files passed as a FILE are skipped.
+
+watch re-runs the day's sample tests and solver whenever its src/, input or sample* files change.";
+
+fn solve_day(day: &Day) {
+    let (part1, part2) = (day.solve)();
+    println!("Day {:02}\n    Part 1: {}\n    Part 2: {}", day.day, part1, part2);
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    process::exit(2);
+}
+
+fn parse_day(arg: &str) -> &'static Day {
+    arg.parse().ok()
+        .and_then(days::find)
+        .unwrap_or_else(|| usage_error(&format!("no solver for day {:?}", arg)))
+}
+
+/// The bundled input of each day followed by the files in its `DIR/dayNN/` directory, sorted by
+/// name, skipping answers files
+fn tasks(days: &[&'static Day], inputs: &Path) -> Vec<Task> {
+    let mut tasks = vec![];
+    for &day in days {
+        tasks.push(Task { day, input: Input::Bundled });
+
+        let mut files = fs::read_dir(inputs.join(format!("day{:02}", day.day)))
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && !batch::is_answers(path))
+            .collect::<Vec<_>>();
+        files.sort();
+        tasks.extend(files.into_iter().map(|path| Task { day, input: Input::File(path) }));
+    }
+    tasks
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+fn parse_jobs(arg: Option<&String>) -> usize {
+    arg.and_then(|n| n.parse().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or_else(|| usage_error("--jobs expects a positive number"))
+}
+
+fn run(args: &[String]) {
+    let mut jobs = default_jobs();
+    let mut inputs = PathBuf::from("inputs");
+    let mut days = vec![];
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--jobs" | "-j" => jobs = parse_jobs(args.next()),
+            "--inputs" => {
+                inputs = args.next()
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| usage_error("--inputs expects a directory"));
+            }
+            day => days.push(parse_day(day)),
+        }
+    }
+    if days.is_empty() {
+        days = DAYS.iter().collect();
+    }
+
+    let tasks = tasks(&days, &inputs);
+    let count = tasks.len();
+    let start = Instant::now();
+    let outcomes = runner::run(tasks, jobs);
+    let wall = start.elapsed();
+
+    let mut failed = 0;
+    for outcome in &outcomes {
+        println!("Day {:02} ({}) in {}", outcome.task.day.day, outcome.task.input, format_duration(outcome.elapsed));
+        match &outcome.answers {
+            Ok((part1, part2)) => println!("    Part 1: {}\n    Part 2: {}", part1, part2),
+            Err(e) => {
+                failed += 1;
+                println!("    error: {}", e)
+            }
+        }
+    }
+
+    let busy = outcomes.iter().map(|outcome| outcome.elapsed).sum::<Duration>();
+    println!(
+        "\n{} tasks in {}, {} solving (--jobs {})",
+        count, format_duration(wall), format_duration(busy), jobs,
+    );
+
+    if failed > 0 {
+        eprintln!("{} of {} tasks failed", failed, count);
+        process::exit(1);
+    }
+}
+
+fn batch(args: &[String]) {
+    let mut jobs = default_jobs();
+    let mut day = None;
+    let mut files = vec![];
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--jobs" | "-j" => jobs = parse_jobs(args.next()),
+            "--day" | "-d" => {
+                day = Some(parse_day(args.next().unwrap_or_else(|| usage_error("--day expects a day"))));
+            }
+            file => files.push(PathBuf::from(file)),
+        }
+    }
+    let day = day.unwrap_or_else(|| usage_error("batch needs a --day"));
+    let files = batch::inputs(files);
+    if files.is_empty() {
+        usage_error("batch needs at least one input file");
+    }
+
+    let tasks = files.into_iter()
+        .map(|path| Task { day, input: Input::File(path) })
+        .collect::<Vec<_>>();
+    let rows = runner::run(tasks, jobs).iter()
+        .map(|outcome| match &outcome.task.input {
+            Input::File(path) => Row::new(outcome, batch::expected(path)),
+            Input::Bundled => Row::new(outcome, None),
+        })
+        .collect::<Vec<_>>();
+
+    println!("Day {:02}\n", day.day);
+    print!("{}", batch::table(&rows));
+
+    let failed = rows.iter().filter(|row| row.is_failure()).count();
+    if failed > 0 {
+        eprintln!("\n{} of {} files failed", failed, rows.len());
+        process::exit(1);
+    }
+}
+
+fn watch(args: &[String]) {
+    let mut interval = Duration::from_millis(500);
+    let mut day = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--interval" => {
+                interval = args.next()
+                    .and_then(|ms| ms.parse().ok())
+                    .map(Duration::from_millis)
+                    .unwrap_or_else(|| usage_error("--interval expects a number of milliseconds"));
+            }
+            arg => day = Some(parse_day(arg)),
+        }
+    }
+    let day = day.unwrap_or_else(|| usage_error("watch needs a day"));
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    if let Err(e) = watch::watch(root, day.day, interval) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
 }
 
 fn main() {
-    solve_day(01, day01::solve);
-    solve_day(02, day02::solve);
-    solve_day(03, day03::solve);
-    solve_day(04, day04::solve);
-    solve_day(05, day05::solve);
-    solve_day(06, day06::solve);
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    match args.first().map(String::as_str) {
+        None => DAYS.iter().for_each(solve_day),
+        Some("run") => run(&args[1..]),
+        Some("batch") => batch(&args[1..]),
+        Some("watch") => watch(&args[1..]),
+        Some("help" | "--help" | "-h") => println!("{}", USAGE),
+        Some(command) => usage_error(&format!("unknown command {:?}", command)),
+    }
 }
 
 #[cfg(all(feature = "unstable", test))]
 mod bench {
     extern crate test;
 
-    use crate::*;
     use test::Bencher;
 
     #[bench]
@@ -75,6 +260,76 @@ mod bench {
         });
     }
 
+    #[bench]
+    fn d08(b: &mut Bencher) {
+        b.iter(|| {
+            day08::solve();
+        });
+    }
+
+    #[bench]
+    fn d09(b: &mut Bencher) {
+        b.iter(|| {
+            day09::solve();
+        });
+    }
+
+    #[bench]
+    fn d10(b: &mut Bencher) {
+        b.iter(|| {
+            day10::solve();
+        });
+    }
+
+    #[bench]
+    fn d11(b: &mut Bencher) {
+        b.iter(|| {
+            day11::solve();
+        });
+    }
+
+    #[bench]
+    fn d12(b: &mut Bencher) {
+        b.iter(|| {
+            day12::solve();
+        });
+    }
+
+    #[bench]
+    fn d13(b: &mut Bencher) {
+        b.iter(|| {
+            day13::solve();
+        });
+    }
+
+    #[bench]
+    fn d14(b: &mut Bencher) {
+        b.iter(|| {
+            day14::solve();
+        });
+    }
+
+    #[bench]
+    fn d15(b: &mut Bencher) {
+        b.iter(|| {
+            day15::solve();
+        });
+    }
+
+    #[bench]
+    fn d16(b: &mut Bencher) {
+        b.iter(|| {
+            day16::solve();
+        });
+    }
+
+    #[bench]
+    fn d17(b: &mut Bencher) {
+        b.iter(|| {
+            day17::solve();
+        });
+    }
+
     #[bench]
     fn all_days(b: &mut Bencher) {
         b.iter(|| {
@@ -84,6 +339,16 @@ mod bench {
             day04::solve();
             day05::solve();
             day06::solve();
+            day08::solve();
+            day09::solve();
+            day10::solve();
+            day11::solve();
+            day12::solve();
+            day13::solve();
+            day14::solve();
+            day15::solve();
+            day16::solve();
+            day17::solve();
         });
     }
 }
@@ -0,0 +1,145 @@
+use std::any::Any;
+use std::fmt;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::days::{Answers, Day};
+
+pub enum Input {
+    /// The puzzle input compiled into the day's crate
+    Bundled,
+    File(PathBuf),
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Input::Bundled => f.write_str("bundled input"),
+            Input::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// One day to solve on one input
+pub struct Task {
+    pub day: &'static Day,
+    pub input: Input,
+}
+
+impl Task {
+    /// Solves the task, turning unreadable input and panicking solvers into an error message.
+    /// The duration covers the solver only, not reading the input.
+    fn run(&self) -> (Result<Answers, String>, Duration) {
+        let input = match &self.input {
+            Input::Bundled => None,
+            Input::File(path) => match fs::read_to_string(path) {
+                Ok(input) => Some(input),
+                Err(e) => return (Err(e.to_string()), Duration::default()),
+            },
+        };
+
+        let start = Instant::now();
+        let answers = panic::catch_unwind(AssertUnwindSafe(|| match &input {
+            None => (self.day.solve)(),
+            Some(input) => (self.day.solve_input)(input),
+        }));
+        let elapsed = start.elapsed();
+
+        (answers.map_err(panic_message), elapsed)
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    let message = payload.downcast_ref::<&str>().map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown error".to_owned());
+    format!("solver panicked: {}", message)
+}
+
+pub struct Outcome {
+    pub task: Task,
+    pub answers: Result<Answers, String>,
+    pub elapsed: Duration,
+}
+
+/// Runs every task on a pool of [jobs] threads.
+///
+/// Outcomes are returned in the order of [tasks], however the threads happened to finish. A
+/// panicking solver's message is reported in its task's outcome. The panic hook is left alone, so
+/// the default hook still prints it to stderr as it happens.
+pub fn run(tasks: Vec<Task>, jobs: usize) -> Vec<Outcome> {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, tasks.len().max(1)) {
+            let sender = sender.clone();
+            let (tasks, next) = (&tasks, &next);
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                match tasks.get(index) {
+                    Some(task) => sender.send((index, task.run())).unwrap(),
+                    None => break,
+                }
+            });
+        }
+    });
+    drop(sender);
+
+    let mut results = receiver.into_iter().collect::<Vec<_>>();
+    results.sort_by_key(|&(index, _)| index);
+
+    tasks.into_iter().zip(results)
+        .map(|(task, (_, (answers, elapsed)))| Outcome { task, answers, elapsed })
+        .collect()
+}
+
+// ============================================================================================== //
+
+#[cfg(test)]
+mod tests {
+    use crate::days::{self, Day};
+    use crate::runner::*;
+
+    const FAILING: Day = Day {
+        day: 0,
+        solve: || panic!("no input"),
+        solve_input: |input| (input.len().to_string(), input.to_owned()),
+    };
+
+    #[test]
+    fn outcomes_in_task_order() {
+        let tasks = (0..20)
+            .map(|i| Task { day: days::find(i % 6 + 1).unwrap(), input: Input::Bundled })
+            .collect::<Vec<_>>();
+        let sequential = run(tasks, 1);
+
+        let tasks = (0..20)
+            .map(|i| Task { day: days::find(i % 6 + 1).unwrap(), input: Input::Bundled })
+            .collect::<Vec<_>>();
+        let parallel = run(tasks, 4);
+
+        assert_eq!(parallel.len(), 20);
+        for (i, (a, b)) in sequential.iter().zip(&parallel).enumerate() {
+            assert_eq!(b.task.day.day, i % 6 + 1);
+            assert_eq!(a.answers, b.answers);
+        }
+    }
+
+    #[test]
+    fn failures_are_reported() {
+        let tasks = vec![
+            Task { day: &FAILING, input: Input::Bundled },
+            Task { day: &FAILING, input: Input::File("does/not/exist".into()) },
+        ];
+        let outcomes = run(tasks, 2);
+
+        assert_eq!(outcomes[0].answers, Err("solver panicked: no input".to_owned()));
+        assert!(outcomes[1].answers.is_err());
+    }
+}
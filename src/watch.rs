@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Modification time and length of every watched file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot(BTreeMap<PathBuf, (SystemTime, u64)>);
+
+impl Snapshot {
+    /// Every file under `src/`, the `input` and the `sample*` files of [day_dir]
+    pub fn take(day_dir: &Path) -> Snapshot {
+        let mut snapshot = Snapshot::default();
+        snapshot.add_dir(&day_dir.join("src"));
+
+        for entry in fs::read_dir(day_dir).into_iter().flatten().flatten() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name == "input" || name.starts_with("sample") {
+                snapshot.add_file(entry.path());
+            }
+        }
+
+        snapshot
+    }
+
+    fn add_dir(&mut self, dir: &Path) {
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                self.add_dir(&path);
+            } else {
+                self.add_file(path);
+            }
+        }
+    }
+
+    fn add_file(&mut self, path: PathBuf) {
+        if let Ok(metadata) = fs::metadata(&path) {
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            self.0.insert(path, (modified, metadata.len()));
+        }
+    }
+
+    /// Files added, removed or modified since [previous]
+    pub fn changes<'a>(&'a self, previous: &'a Snapshot) -> Vec<&'a Path> {
+        let modified = self.0.iter()
+            .filter(|&(path, stamp)| previous.0.get(path) != Some(stamp))
+            .map(|(path, _)| path.as_path());
+        let removed = previous.0.keys()
+            .filter(|&path| !self.0.contains_key(path))
+            .map(PathBuf::as_path);
+        modified.chain(removed).collect()
+    }
+}
+
+/// Line by line differences between two solver outputs, `-` for the previous line and `+` for
+/// the current one
+pub fn diff(previous: &str, current: &str) -> Vec<String> {
+    let previous = previous.lines().collect::<Vec<_>>();
+    let current = current.lines().collect::<Vec<_>>();
+
+    let mut out = vec![];
+    for i in 0..previous.len().max(current.len()) {
+        match (previous.get(i), current.get(i)) {
+            (Some(a), Some(b)) if a == b => {}
+            (a, b) => {
+                out.extend(a.map(|line| format!("- {}", line)));
+                out.extend(b.map(|line| format!("+ {}", line)));
+            }
+        }
+    }
+    out
+}
+
+/// The solver's answers from the output of `aoc2020 run`, without the timings that change every run
+fn answers(output: &str) -> String {
+    output.lines()
+        .filter(|line| line.starts_with("    "))
+        .map(|line| format!("{}\n", line.trim()))
+        .collect()
+}
+
+fn cargo(root: &Path) -> Command {
+    let mut command = Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    command.current_dir(root);
+    command
+}
+
+/// Runs the day's sample tests, then its solver, printing how long each took and how the answers
+/// changed since [previous]. Returns the new answers.
+fn check(root: &Path, day: usize, previous: Option<&str>) -> io::Result<Option<String>> {
+    let day_dir = root.join(format!("day{:02}", day));
+
+    let start = Instant::now();
+    let tests = cargo(root)
+        .args(["test", "--quiet", "--manifest-path"])
+        .arg(day_dir.join("Cargo.toml"))
+        .arg("sample")
+        .status()?;
+    println!("sample tests {} in {:.2}s", if tests.success() { "passed" } else { "FAILED" }, start.elapsed().as_secs_f64());
+
+    let start = Instant::now();
+    let output = cargo(root)
+        .args(["run", "--quiet", "--", "run", "--jobs", "1", &day.to_string()])
+        .stderr(Stdio::inherit())
+        .output()?;
+    let elapsed = start.elapsed();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    print!("{}", stdout);
+
+    if !output.status.success() {
+        println!("solver FAILED after {:.2}s", elapsed.as_secs_f64());
+        return Ok(None);
+    }
+    println!("built and solved in {:.2}s", elapsed.as_secs_f64());
+
+    let answers = answers(&stdout);
+    if let Some(previous) = previous {
+        let diff = diff(previous, &answers);
+        if diff.is_empty() {
+            println!("answers unchanged");
+        } else {
+            println!("answers changed:\n{}", diff.join("\n"));
+        }
+    }
+
+    Ok(Some(answers))
+}
+
+/// Polls the files of [day] in the repository at [root] every [interval], checking the day
+/// whenever any of them changes. Never returns unless a command can't be started.
+pub fn watch(root: &Path, day: usize, interval: Duration) -> io::Result<()> {
+    let day_dir = root.join(format!("day{:02}", day));
+    let mut snapshot = Snapshot::take(&day_dir);
+    let mut previous = None;
+
+    println!("watching {}", day_dir.display());
+    loop {
+        if let Some(answers) = check(root, day, previous.as_deref())? {
+            previous = Some(answers);
+        }
+        println!("\nwaiting for changes...");
+
+        loop {
+            thread::sleep(interval);
+            let current = Snapshot::take(&day_dir);
+            let changes = current.changes(&snapshot);
+            if !changes.is_empty() {
+                println!();
+                for path in changes {
+                    println!("changed: {}", path.strip_prefix(root).unwrap_or(path).display());
+                }
+                snapshot = current;
+                break;
+            }
+        }
+    }
+}
+
+// ============================================================================================== //
+
+#[cfg(test)]
+mod tests {
+    use crate::watch::*;
+
+    #[test]
+    fn snapshot_changes() {
+        let dir = std::env::temp_dir().join(format!("aoc2020-watch-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src/nested")).unwrap();
+        fs::write(dir.join("src/nested/lib.rs"), "a").unwrap();
+        fs::write(dir.join("input"), "1").unwrap();
+        fs::write(dir.join("notes"), "ignored").unwrap();
+
+        let before = Snapshot::take(&dir);
+        assert_eq!(before.0.len(), 2);
+
+        fs::write(dir.join("notes"), "still ignored").unwrap();
+        assert_eq!(Snapshot::take(&dir).changes(&before), Vec::<&Path>::new());
+
+        fs::write(dir.join("src/nested/lib.rs"), "ab").unwrap();
+        fs::write(dir.join("sample01"), "2").unwrap();
+        fs::remove_file(dir.join("input")).unwrap();
+        let after = Snapshot::take(&dir);
+        assert_eq!(after.changes(&before), vec![
+            dir.join("sample01").as_path(),
+            dir.join("src/nested/lib.rs").as_path(),
+            dir.join("input").as_path(),
+        ]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn answer_diff() {
+        let output = "Day 07 (bundled input) in 0.001ms\n    Part 1: 4\n    Part 2: 32\n\n1 tasks in 0.1ms";
+        assert_eq!(answers(output), "Part 1: 4\nPart 2: 32\n");
+
+        assert_eq!(diff("Part 1: 4\nPart 2: 32\n", "Part 1: 4\nPart 2: 32\n"), Vec::<String>::new());
+        assert_eq!(diff("Part 1: \nPart 2: \n", "Part 1: 4\nPart 2: \n"), vec!["- Part 1: ", "+ Part 1: 4"]);
+        assert_eq!(diff("Part 1: 4\n", "Part 1: 4\nerror: oops\n"), vec!["+ error: oops"]);
+    }
+}